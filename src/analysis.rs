@@ -0,0 +1,100 @@
+use crate::model::{
+    clan::Clan,
+    class::{Classes, ClassType, Role},
+    profile::Profile,
+    race::Race,
+};
+
+use std::collections::BTreeMap;
+
+/// Summary statistics for the levels of one class across many profiles.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LevelStats {
+    /// How many profiles have the class unlocked.
+    pub count: usize,
+    /// The lowest level among those profiles.
+    pub min: u32,
+    /// The highest level among those profiles.
+    pub max: u32,
+    /// The mean level among those profiles.
+    pub average: f64,
+}
+
+/// Computes level statistics for every class unlocked by at least one of
+/// the given profiles, such as the members of a free company.
+pub fn job_level_distribution(profiles: &[Profile]) -> BTreeMap<ClassType, LevelStats> {
+    let mut levels: BTreeMap<ClassType, Vec<u32>> = BTreeMap::new();
+
+    for profile in profiles {
+        for (kind, info) in profile.all_class_info().into_iter().flat_map(|classes| classes.iter()) {
+            levels.entry(kind).or_default().push(info.level);
+        }
+    }
+
+    levels.into_iter()
+        .map(|(kind, levels)| {
+            let stats = LevelStats {
+                count: levels.len(),
+                min: levels.iter().copied().min().unwrap_or(0),
+                max: levels.iter().copied().max().unwrap_or(0),
+                average: levels.iter().map(|level| *level as f64).sum::<f64>() / levels.len() as f64,
+            };
+            (kind, stats)
+        })
+        .collect()
+}
+
+/// Counts how many profiles have each role as their most played one,
+/// judged by which role their highest level class belongs to.
+/// The result is sorted with the most common role first.
+pub fn most_played_roles(profiles: &[Profile]) -> Vec<(Role, usize)> {
+    let mut counts: BTreeMap<Role, usize> = BTreeMap::new();
+
+    for profile in profiles {
+        let highest = profile.all_class_info().and_then(Classes::highest_level_job);
+
+        if let Some((kind, _)) = highest {
+            *counts.entry(kind.role()).or_default() += 1;
+        }
+    }
+
+    let mut counts = counts.into_iter().collect::<Vec<_>>();
+    counts.sort_by(|(a_role, a), (b_role, b)| b.cmp(a).then(a_role.cmp(b_role)));
+    counts
+}
+
+/// Counts how many of the profiles are of each race.
+pub fn race_breakdown(profiles: &[Profile]) -> BTreeMap<Race, usize> {
+    let mut counts = BTreeMap::new();
+
+    for profile in profiles {
+        *counts.entry(profile.race).or_default() += 1;
+    }
+
+    counts
+}
+
+/// Counts how many of the profiles are of each clan.
+pub fn clan_breakdown(profiles: &[Profile]) -> BTreeMap<Clan, usize> {
+    let mut counts = BTreeMap::new();
+
+    for profile in profiles {
+        *counts.entry(profile.clan).or_default() += 1;
+    }
+
+    counts
+}
+
+/// The mean of the average item levels of the given profiles. Profiles
+/// without any gear equipped are left out.
+pub fn average_item_level(profiles: &[Profile]) -> Option<f64> {
+    let levels = profiles.iter()
+        .filter_map(|profile| profile.average_item_level())
+        .collect::<Vec<u32>>();
+
+    if levels.is_empty() {
+        return None;
+    }
+
+    Some(levels.iter().map(|level| *level as f64).sum::<f64>() / levels.len() as f64)
+}
@@ -0,0 +1,76 @@
+use failure::Error;
+use lazy_static::lazy_static;
+use select::document::Document;
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::RwLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::model::profile::SearchError;
+
+lazy_static! {
+    static ref DUMP_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+}
+
+/// Sets a directory to write diagnostics into whenever a page fails to parse.
+/// Passing None turns the diagnostics back off, which is the default.
+///
+/// Each failure gets its own subdirectory containing every page involved
+/// and an `error.txt` describing what went wrong, so it can be attached
+/// to a bug report when the lodestone layout changes. This covers every
+/// page fetched by id, such as profiles, free companies, linkshells,
+/// PvP teams, achievements, blogs, mounts and minions, but not search
+/// results.
+pub fn set_dump_dir(dir: Option<PathBuf>) {
+    *DUMP_DIR.write().unwrap_or_else(|e| e.into_inner()) = dir;
+}
+
+/// Gets the directory parse failures are written into, if any.
+pub fn dump_dir() -> Option<PathBuf> {
+    DUMP_DIR.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Writes out the pages that produced a parse error, if a dump directory
+/// is set. `context` names what was being parsed, e.g. `profile-11908971`.
+///
+/// Failing to write the dump is ignored so the parse error is what the
+/// caller sees.
+pub(crate) fn dump_parse_failure(context: &str, pages: &[(&str, &str)], error: &Error) {
+    let dir = match dump_dir() {
+        Some(dir) => dir,
+        None => return,
+    };
+
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_millis())
+        .unwrap_or(0);
+    let dir = dir.join(format!("{}-{}", context, millis));
+
+    if fs::create_dir_all(&dir).is_err() {
+        return;
+    }
+
+    for (name, html) in pages {
+        let _ = fs::write(dir.join(format!("{}.html", name)), html);
+    }
+
+    let _ = fs::write(dir.join("error.txt"), format!("{}\n\n{:?}", error, error));
+}
+
+/// Parses a single downloaded page, and writes it out with
+/// `dump_parse_failure` if that fails.
+///
+/// A section the character has hidden is not a parse failure, so it is
+/// returned without being dumped.
+pub(crate) fn parse_or_dump<T, F>(context: &str, html: &str, parse: F) -> Result<T, Error>
+where
+    F: FnOnce(&Document) -> Result<T, Error>,
+{
+    parse(&Document::from(html)).inspect_err(|e| {
+        if !matches!(e.downcast_ref::<SearchError>(), Some(SearchError::SectionPrivate(_))) {
+            dump_parse_failure(context, &[("page", html)], e);
+        }
+    })
+}
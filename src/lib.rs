@@ -1,7 +1,12 @@
 #[allow(unused)]
 
+pub mod analysis;
+pub mod diagnostics;
 pub mod model;
+pub mod pagination;
 pub mod search;
+#[cfg(feature = "vcr")]
+pub mod vcr;
 
 // Lazy static client to avoid creating new ones every time
 lazy_static::lazy_static! {
@@ -10,6 +15,248 @@ lazy_static::lazy_static! {
 
 #[cfg(test)]
 mod tests {
+    /// A trimmed down free company page, for the parsing tests.
+    const FREE_COMPANY_HTML: &str = r#"<div class="ldst__window">
+        <div class="entry__freecompany__crest__image"><img src="https://img.finalfantasyxiv.com/crest/base.png"><img src="https://img.finalfantasyxiv.com/crest/frame.png"><img src="https://img.finalfantasyxiv.com/crest/emblem.png"></div>
+        <p class="entry__freecompany__gc">Maelstrom &lt;Neutral&gt;</p>
+        <p class="entry__freecompany__name">Crimson Custard</p>
+        <p class="entry__freecompany__gc">Famfrit&nbsp;[Primal]</p>
+        <h3 class="heading--lead">Company Slogan</h3>
+        <p class="freecompany__text freecompany__text__message">Come for the custard,<br>stay for the raids.</p>
+        <h3 class="heading--lead">Company Tag</h3>
+        <p class="freecompany__text freecompany__text__tag">«CSTD»</p>
+        <h3 class="heading--lead">Formed</h3>
+        <p class="freecompany__text"><span id="datetime-1"></span><script>document.getElementById('datetime-1').innerHTML = ldst_strftime(1381000000, 'YMD');</script></p>
+        <h3 class="heading--lead">Active Members</h3>
+        <p class="freecompany__text">128</p>
+        <h3 class="heading--lead">Rank</h3>
+        <p class="freecompany__text">30</p>
+        <h3 class="heading--lead">Recruitment</h3>
+        <p class="freecompany__text freecompany__recruitment">Open</p>
+        <h3 class="heading--lead">Active</h3>
+        <p class="freecompany__text">Weekends Only</p>
+        <h3 class="heading--lead">Focus</h3>
+        <ul class="freecompany__focus_icon">
+            <li><img src="rp.png"><p>Role-playing</p></li>
+            <li class="freecompany__focus_icon--off"><img src="lv.png"><p>Leveling</p></li>
+            <li><img src="raid.png"><p>Raids</p></li>
+        </ul>
+        <h3 class="heading--lead">Seeking</h3>
+        <ul class="freecompany__focus_icon freecompany__focus_icon--role">
+            <li><img src="tank.png"><p>Tank</p></li>
+            <li class="freecompany__focus_icon--off"><img src="healer.png"><p>Healer</p></li>
+            <li><img src="dps.png"><p>DPS</p></li>
+        </ul>
+        <h3 class="heading--lead">Estate Profile</h3>
+        <p class="freecompany__estate__name">Custard Manor</p>
+        <p class="freecompany__estate__title">Address</p>
+        <p class="freecompany__estate__text">Plot 5, 12 Ward, Mist (Medium)</p>
+        <p class="freecompany__estate__title">Greeting</p>
+        <p class="freecompany__estate__greeting">Welcome!</p>
+        <h3 class="heading--lead">Ranking</h3>
+        <table class="character__ranking__data">
+            <tr><th>Weekly Rank: 12 (Previous Week: --)</th></tr>
+            <tr><th>Monthly Rank: 40 (Previous Month: 35)</th></tr>
+        </table>
+        <h3 class="heading--lead">Reputation</h3>
+        <div class="freecompany__reputation"><p class="freecompany__reputation__gcname">Maelstrom</p><p class="freecompany__reputation__rank">Allied</p></div>
+        <div class="freecompany__reputation"><p class="freecompany__reputation__gcname">Order of the Twin Adder</p><p class="freecompany__reputation__rank">Friendly</p></div>
+        <div class="freecompany__reputation"><p class="freecompany__reputation__gcname">Immortal Flames</p><p class="freecompany__reputation__rank">Neutral</p></div>
+    </div>"#;
+
+    #[test]
+    fn can_grab_free_company() {
+        use crate::model::free_company::FreeCompany;
+
+        assert!(FreeCompany::get(9229142273877347916).is_ok());
+    }
+
+    #[test]
+    fn can_parse_free_company() {
+        use crate::model::{
+            class::Role,
+            datacenter::Datacenter,
+            free_company::{ActiveTime, Focus, FreeCompany, Reputation},
+            gc::GrandCompany,
+        };
+
+        let fc = FreeCompany::parse_from_html(9229142273877347916, FREE_COMPANY_HTML).unwrap();
+        assert_eq!(fc.name, "Crimson Custard");
+        assert_eq!(fc.lodestone_url(), "https://na.finalfantasyxiv.com/lodestone/freecompany/9229142273877347916/");
+        assert_eq!(fc.tag, "CSTD");
+        assert_eq!(fc.slogan.as_deref(), Some("Come for the custard,\nstay for the raids."));
+        assert_eq!(fc.crest.len(), 3);
+        assert!(fc.crest[2].ends_with("emblem.png"));
+        assert_eq!(fc.grand_company, GrandCompany::Maelstrom);
+        assert_eq!(fc.server.to_string(), "Famfrit");
+        assert_eq!(fc.datacenter, Datacenter::Primal);
+        assert_eq!(fc.rank, 30);
+        assert_eq!(fc.formed_at, Some(1381000000));
+        assert_eq!(fc.active_members, 128);
+        assert!(fc.recruiting);
+        assert_eq!(fc.active, Some(ActiveTime::Weekends));
+        assert_eq!(fc.focus, vec![Focus::RolePlaying, Focus::Raids].into_iter().collect());
+        assert_eq!(fc.seeking, vec![Role::Tank, Role::Damage].into_iter().collect());
+        assert_eq!(fc.ranking.weekly.current, Some(12));
+        assert_eq!(fc.ranking.weekly.previous, None);
+        assert_eq!(fc.ranking.monthly.current, Some(40));
+        assert_eq!(fc.ranking.monthly.previous, Some(35));
+        assert_eq!(fc.reputation[&GrandCompany::Maelstrom], Reputation::Allied);
+        assert_eq!(fc.reputation[&GrandCompany::TwinAdder], Reputation::Friendly);
+        assert!(fc.reputation[&GrandCompany::ImmortalFlames] < Reputation::Recognized);
+
+        let estate = fc.estate.unwrap();
+        assert_eq!(estate.name, "Custard Manor");
+        assert_eq!(estate.address, "Plot 5, 12 Ward, Mist (Medium)");
+        assert_eq!(estate.greeting.as_deref(), Some("Welcome!"));
+
+        let homeless = FREE_COMPANY_HTML.replace(r#"<p class="freecompany__estate__name">Custard Manor</p>"#, r#"<p class="freecompany__estate__none">No Estate or Plot</p>"#);
+        let fc = FreeCompany::parse_from_html(9229142273877347916, &homeless).unwrap();
+        assert_eq!(fc.estate, None);
+    }
+
+    #[test]
+    fn can_parse_free_company_members() {
+        use crate::model::{datacenter::Datacenter, free_company::parse_member_list};
+        use select::document::Document;
+
+        let html = r#"<ul>
+            <li class="entry"><a href="/lodestone/character/11908971/" class="entry__bg">
+                <div class="entry__chara__face"><img src="https://img.finalfantasyxiv.com/face/strawberry.jpg"></div>
+                <p class="entry__name">Strawberry Custard</p>
+                <p class="entry__world">Famfrit&nbsp;[Primal]</p>
+                <ul class="entry__freecompany__info"><li><img src="https://img.finalfantasyxiv.com/rank/master.png"><span>Master</span></li></ul>
+            </a></li>
+            <li class="entry"><a href="/lodestone/character/123/" class="entry__bg">
+                <p class="entry__name">Vanilla Custard</p>
+                <p class="entry__world">Exodus&nbsp;[Primal]</p>
+                <ul class="entry__freecompany__info"><li><span>Recruit</span></li></ul>
+            </a></li>
+        </ul>"#;
+
+        let members = parse_member_list(&Document::from(html)).unwrap();
+        assert_eq!(members.len(), 2);
+        assert_eq!(members[0].entry.id, 11908971);
+        assert_eq!(members[0].entry.name, "Strawberry Custard");
+        assert_eq!(members[0].entry.world.to_string(), "Famfrit");
+        assert_eq!(members[0].entry.datacenter, Datacenter::Primal);
+        assert_eq!(members[0].entry.avatar.as_deref(), Some("https://img.finalfantasyxiv.com/face/strawberry.jpg"));
+        assert_eq!(members[0].rank_name, "Master");
+        assert_eq!(members[0].rank_icon.as_deref(), Some("https://img.finalfantasyxiv.com/rank/master.png"));
+        assert_eq!(members[1].rank_name, "Recruit");
+        assert_eq!(members[1].rank_icon, None);
+    }
+
+    #[test]
+    fn can_diff_rosters() {
+        use crate::model::{datacenter::Datacenter, free_company::FcMember, member::MemberEntry, roster::RosterDelta, server::Server};
+
+        let member = |id, rank: &str| FcMember {
+            entry: MemberEntry {
+                id,
+                name: format!("Member {}", id),
+                world: Server::Famfrit,
+                datacenter: Datacenter::Primal,
+                avatar: None,
+            },
+            rank_name: rank.into(),
+            rank_icon: None,
+        };
+
+        let old = vec![member(1, "Master"), member(2, "Recruit"), member(3, "Recruit")];
+        let new = vec![member(1, "Master"), member(2, "Officer"), member(4, "Recruit")];
+
+        let delta = RosterDelta::between(&old, &new);
+        assert_eq!(delta.joined.iter().map(|m| m.entry.id).collect::<Vec<_>>(), vec![4]);
+        assert_eq!(delta.left.iter().map(|m| m.entry.id).collect::<Vec<_>>(), vec![3]);
+        assert_eq!(delta.rank_changed.len(), 1);
+        assert_eq!(delta.rank_changed[0].member.rank_name, "Officer");
+        assert_eq!(delta.rank_changed[0].previous_rank, "Recruit");
+        assert!(RosterDelta::between(&new, &new).is_empty());
+    }
+
+    #[test]
+    fn can_parse_pvp_team() {
+        use crate::model::{datacenter::Datacenter, pvp_team::{PvpTeam, PvpTeamRank}};
+
+        let html = r#"<div class="ldst__window">
+            <div class="entry__pvpteam__crest__image"><img src="base.png"><img src="emblem.png"></div>
+            <h2 class="entry__pvpteam__name--team">Custard Cavalry</h2>
+            <p class="entry__pvpteam__name--dc">Primal</p>
+            <p class="entry__pvpteam__data--formed"><span id="datetime-1"></span><script>ldst_strftime(1500000000, 'YMD');</script></p>
+            <ul>
+                <li class="entry"><a href="/lodestone/character/11908971/" class="entry__bg">
+                    <p class="entry__name">Strawberry Custard</p>
+                    <p class="entry__world">Famfrit&nbsp;[Primal]</p>
+                    <div class="entry__chara_info__pvpteam"><img src="leader.png"><span>Leader</span></div>
+                    <ul class="entry__freecompany__info"><li><span>Matches: 42</span></li></ul>
+                </a></li>
+                <li class="entry"><a href="/lodestone/character/123/" class="entry__bg">
+                    <p class="entry__name">Vanilla Custard</p>
+                    <p class="entry__world">Exodus&nbsp;[Primal]</p>
+                    <ul class="entry__freecompany__info"><li><span>Matches: 7</span></li></ul>
+                </a></li>
+            </ul>
+        </div>"#;
+
+        let team = PvpTeam::parse_from_html("c7a0a3d0e4b1f2a9", html).unwrap();
+        assert_eq!(team.name, "Custard Cavalry");
+        assert_eq!(team.lodestone_url(), "https://na.finalfantasyxiv.com/lodestone/pvpteam/c7a0a3d0e4b1f2a9/");
+        assert_eq!(team.datacenter, Datacenter::Primal);
+        assert_eq!(team.crest.len(), 2);
+        assert_eq!(team.formed_at, Some(1500000000));
+        assert_eq!(team.members.len(), 2);
+        assert_eq!(team.members[0].rank, PvpTeamRank::Leader);
+        assert_eq!(team.members[0].matches, 42);
+        assert_eq!(team.members[1].entry.id, 123);
+        assert_eq!(team.members[1].rank, PvpTeamRank::Member);
+        assert_eq!(team.members[1].matches, 7);
+    }
+
+    #[test]
+    fn can_parse_linkshell() {
+        use crate::model::{datacenter::Datacenter, linkshell::{Linkshell, LinkshellRank}, member::MemberEntry};
+
+        let html = r#"<div class="ldst__window">
+            <h3 class="heading__linkshell__name">Custard Club</h3>
+            <p class="heading__linkshell__world">Famfrit&nbsp;[Primal]</p>
+            <div class="parts__total">3 Total</div>
+            <ul>
+                <li class="entry"><a href="/lodestone/character/11908971/" class="entry__bg">
+                    <p class="entry__name">Strawberry Custard</p>
+                    <p class="entry__world">Famfrit&nbsp;[Primal]</p>
+                    <div class="entry__chara_info__linkshell"><img src="master.png"><span>Master</span></div>
+                </a></li>
+                <li class="entry"><a href="/lodestone/character/123/" class="entry__bg">
+                    <p class="entry__name">Vanilla Custard</p>
+                    <p class="entry__world">Exodus&nbsp;[Primal]</p>
+                    <div class="entry__chara_info__linkshell"><img src="leader.png"><span>Leader</span></div>
+                </a></li>
+                <li class="entry"><a href="/lodestone/character/456/" class="entry__bg">
+                    <p class="entry__name">Chocolate Custard</p>
+                    <p class="entry__world">Behemoth&nbsp;[Primal]</p>
+                </a></li>
+            </ul>
+        </div>"#;
+
+        let ls = Linkshell::parse_from_html(19984723346535274, html).unwrap();
+        assert_eq!(ls.name, "Custard Club");
+        assert_eq!(ls.lodestone_url(), "https://na.finalfantasyxiv.com/lodestone/linkshell/19984723346535274/");
+        assert_eq!(ls.world.to_string(), "Famfrit");
+        assert_eq!(ls.datacenter, Datacenter::Primal);
+        assert_eq!(ls.member_count, 3);
+        assert_eq!(ls.members.iter().map(|m| m.rank).collect::<Vec<_>>(),
+            vec![LinkshellRank::Master, LinkshellRank::Leader, LinkshellRank::Member]);
+        assert_eq!(ls.members[1].entry.id, 123);
+        assert_eq!(ls.members[2].entry.world.to_string(), "Behemoth");
+
+        //  Rosters of different groups can be handled through their shared entries.
+        fn names<M: AsRef<MemberEntry>>(members: &[M]) -> Vec<&str> {
+            members.iter().map(|member| member.as_ref().name.as_str()).collect()
+        }
+        assert_eq!(names(&ls.members), vec!["Strawberry Custard", "Vanilla Custard", "Chocolate Custard"]);
+    }
+
     #[test]
     fn can_grab_profile() {
         use crate::model::profile::Profile;
@@ -17,6 +264,96 @@ mod tests {
         assert!(Profile::get(11908971).is_ok());
     }
 
+    #[test]
+    fn can_grab_mounts() {
+        use crate::model::mount::Mounts;
+
+        assert!(Mounts::get(11908971).is_ok());
+    }
+
+    #[test]
+    fn can_parse_mounts() {
+        use crate::model::mount::Mounts;
+        use select::document::Document;
+
+        let doc = Document::from(r#"<div class="mount__sort__total"><span>1,002</span> Total</div>
+            <ul>
+                <li class="mount__list__item">
+                    <img class="mount__list__icon__image" src="https://img.finalfantasyxiv.com/mount/chocobo.png">
+                    <span class="mount__name">Company Chocobo</span>
+                </li>
+                <li class="mount__list__item">
+                    <img class="mount__list__icon__image" src="https://img.finalfantasyxiv.com/mount/magitek.png">
+                    <span class="mount__name">Magitek Armor</span>
+                </li>
+            </ul>"#);
+
+        let mounts = Mounts::parse(&doc).unwrap();
+        assert_eq!(mounts.len(), 2);
+        assert_eq!(mounts.total(), 1002);
+        assert!(mounts.contains("magitek armor"));
+        assert!(!mounts.contains("Fat Chocobo"));
+
+        let mounts = Mounts::parse(&Document::from("<ul></ul>")).unwrap();
+        assert!(mounts.is_empty());
+        assert_eq!(mounts.total(), 0);
+    }
+
+    #[test]
+    fn can_parse_minions() {
+        use crate::model::minion::Minions;
+        use select::document::Document;
+
+        let doc = Document::from(r#"<div class="minion__sort__total"><span>1,234</span> Total</div>
+            <ul>
+                <li class="minion__list__item">
+                    <img class="minion__list__icon__image" src="https://img.finalfantasyxiv.com/minion/wind-up.png">
+                    <span class="minion__name">Wind-up Cursor</span>
+                </li>
+            </ul>"#);
+
+        let minions = Minions::parse(&doc).unwrap();
+        assert_eq!(minions.len(), 1);
+        assert_eq!(minions.total(), 1234);
+        assert!(minions.contains("wind-up cursor"));
+        assert_eq!(minions.iter().next().unwrap().icon, "https://img.finalfantasyxiv.com/minion/wind-up.png");
+    }
+
+    #[test]
+    fn can_grab_profile_without_classes() {
+        use crate::model::profile::ProfileOptions;
+
+        let profile = ProfileOptions::new().classes(false).get(11908971).unwrap();
+        assert!(profile.all_class_info().is_none());
+    }
+
+    #[test]
+    fn can_grab_many_profiles() {
+        use crate::model::profile::Profile;
+        use std::time::Duration;
+
+        let profiles = Profile::get_many(&[11908971, 1], 2, Duration::from_millis(100));
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0].as_ref().unwrap().user_id, 11908971);
+        assert!(profiles[1].is_err());
+        assert!(Profile::get_many(&[], 2, Duration::from_millis(100)).is_empty());
+    }
+
+    #[test]
+    fn can_grab_profile_existence() {
+        use crate::model::profile::Profile;
+
+        assert!(Profile::exists(11908971).unwrap());
+        assert!(!Profile::exists(1).unwrap());
+    }
+
+    #[test]
+    fn can_grab_minions() {
+        use crate::model::minion::Minions;
+
+        assert!(Minions::get(11908971).is_ok());
+    }
+
     #[test]
     fn can_create_search() {
         use crate::model::datacenter::Datacenter;
@@ -35,9 +372,801 @@ mod tests {
         assert_eq!(profiles.len(), 1);
     }
 
+    #[test]
+    fn can_parse_lodestone_urls() {
+        use crate::model::id::LodestoneId;
+        use std::convert::TryFrom;
+
+        assert_eq!(
+            LodestoneId::try_from("https://na.finalfantasyxiv.com/lodestone/character/11908971/").unwrap(),
+            LodestoneId::Character(11908971)
+        );
+        assert_eq!(
+            LodestoneId::try_from("https://jp.finalfantasyxiv.com/lodestone/character/11908971/class_job/?hl=en").unwrap(),
+            LodestoneId::Character(11908971)
+        );
+        assert_eq!(
+            LodestoneId::try_from("eu.finalfantasyxiv.com/lodestone/freecompany/9229142273877347916").unwrap(),
+            LodestoneId::FreeCompany(9229142273877347916)
+        );
+        assert_eq!(
+            LodestoneId::try_from("https://de.finalfantasyxiv.com/lodestone/linkshell/19984723346535274").unwrap(),
+            LodestoneId::Linkshell(19984723346535274)
+        );
+        assert_eq!(
+            LodestoneId::try_from("https://na.finalfantasyxiv.com/lodestone/pvpteam/c7a8f9d3e2b1a0f4/").unwrap(),
+            LodestoneId::PvpTeam("c7a8f9d3e2b1a0f4".into())
+        );
+
+        assert!(LodestoneId::try_from("https://na.finalfantasyxiv.com/lodestone/character/").is_err());
+        assert!(LodestoneId::try_from("https://na.finalfantasyxiv.com/lodestone/worldstatus/").is_err());
+        assert!(LodestoneId::try_from("https://example.com/character/11908971").is_err());
+        assert!(LodestoneId::try_from("https://na.finalfantasyxiv.com/lodestone/pvpteam/not-hex/").is_err());
+
+        let id = LodestoneId::FreeCompany(9229142273877347916);
+        assert_eq!(id.lodestone_url(), "https://na.finalfantasyxiv.com/lodestone/freecompany/9229142273877347916/");
+        assert_eq!(LodestoneId::try_from(id.lodestone_url().as_str()).unwrap(), id);
+    }
+
+    #[test]
+    fn pager_items_walk_every_page() {
+        use crate::pagination::{Page, Paginated, Pager};
+        use failure::{Error, format_err};
+
+        struct Numbers;
+
+        impl Paginated for Numbers {
+            type Item = u32;
+
+            fn fetch_page(&self, page: u32) -> Result<Page<u32>, Error> {
+                match page {
+                    3 => Err(format_err!("page {} is broken", page)),
+                    _ => Ok(Page { items: vec![page * 10, page * 10 + 1], current_page: page, total_pages: 4 }),
+                }
+            }
+        }
+
+        let mut items = Pager::new(Numbers).items();
+        assert_eq!(items.next().unwrap().unwrap(), 10);
+        assert_eq!(items.next().unwrap().unwrap(), 11);
+        assert_eq!(items.next().unwrap().unwrap(), 20);
+        assert_eq!(items.next().unwrap().unwrap(), 21);
+        assert!(items.next().unwrap().is_err());
+        assert!(items.next().is_none());
+
+        //  A failed page leaves the total as it was.
+        let mut pager = Pager::new(Numbers);
+        pager.next_page().unwrap().unwrap();
+        pager.next_page().unwrap().unwrap();
+        assert!(pager.next_page().unwrap().is_err());
+        assert_eq!(pager.total_pages(), Some(4));
+        assert!(pager.next_page().is_none());
+
+        //  Only the pages after the first are delayed.
+        let delay = std::time::Duration::from_millis(20);
+        let start = std::time::Instant::now();
+        let pages = Pager::new(Numbers).delay(delay).take(2).collect::<Vec<_>>();
+        assert_eq!(pages.len(), 2);
+        assert!(start.elapsed() >= delay);
+    }
+
+    #[test]
+    fn can_parse_page_numbers() {
+        use crate::pagination::parse_page_numbers;
+        use select::document::Document;
+
+        let doc = Document::from(r#"<ul><li class="btn__pager__current">Page 2 of 14</li></ul>"#);
+        assert_eq!(parse_page_numbers(&doc), (2, 14));
+
+        let doc = Document::from(r#"<div class="ldst__window"></div>"#);
+        assert_eq!(parse_page_numbers(&doc), (1, 1));
+    }
+
+    #[test]
+    fn stat_formulas_are_correct() {
+        use crate::model::stats;
+
+        assert_eq!(stats::critical_hit_rate(420), 5.0);
+        assert_eq!(stats::critical_hit_damage(420), 1.4);
+        assert_eq!(stats::critical_hit_rate(3000), 23.5);
+        assert_eq!(stats::critical_hit_damage(3000), 1.585);
+        assert_eq!(stats::direct_hit_rate(420), 0.0);
+        assert_eq!(stats::direct_hit_rate(1500), 21.3);
+        assert_eq!(stats::determination_multiplier(440), 1.0);
+        assert_eq!(stats::determination_multiplier(2000), 1.078);
+        assert_eq!(stats::tenacity_multiplier(420), 1.0);
+        assert_eq!(stats::gcd(420, stats::BASE_GCD), 2.5);
+        assert_eq!(stats::gcd(1000, stats::BASE_GCD), 2.43);
+    }
+
+    #[test]
+    fn attributes_keep_display_order() {
+        use crate::model::attribute::{Attribute, AttributeKind, Attributes};
+
+        let mut attribs = Attributes::new();
+        attribs.insert("Strength".into(), Attribute { level: 130 });
+        attribs.insert("Dexterity".into(), Attribute { level: 295 });
+        attribs.insert("Vitality".into(), Attribute { level: 2937 });
+        attribs.insert("Strength".into(), Attribute { level: 131 });
+        attribs.insert("Direct Hit".into(), Attribute { level: 1183 });
+
+        assert_eq!(attribs.len(), 4);
+        assert_eq!(attribs.get(AttributeKind::DirectHit).unwrap().level, 1183);
+        assert_eq!(attribs.get(AttributeKind::Strength).unwrap().level, 131);
+
+        let names = attribs.iter().map(|(name, _)| name).collect::<Vec<_>>();
+        assert_eq!(names, vec!["Strength", "Dexterity", "Vitality", "Direct Hit Rate"]);
+    }
+
+    #[test]
+    fn parsing_empty_html_fails() {
+        use crate::model::profile::Profile;
+
+        assert!(Profile::parse_from_html(11908971, "", "").is_err());
+    }
+
+    #[test]
+    fn can_derive_image_sizes() {
+        use crate::model::images::{CharacterImages, ImageSize};
+
+        let images = CharacterImages::from_url("https://img2.finalfantasyxiv.com/f/abc_def_l0_640x873.jpg?1234").unwrap();
+        assert_eq!(images.avatar, "https://img2.finalfantasyxiv.com/f/abc_def_c0_96x96.jpg?1234");
+        assert_eq!(images.url(ImageSize::Portrait), "https://img2.finalfantasyxiv.com/f/abc_def_l0_640x873.jpg?1234");
+        assert!(CharacterImages::from_url("https://img2.finalfantasyxiv.com/f/abc.png").is_none());
+    }
+
+    #[test]
+    fn can_parse_attribute_kinds() {
+        use crate::model::attribute::AttributeKind;
+
+        assert_eq!("Critical Hit Rate".parse::<AttributeKind>().unwrap(), AttributeKind::CriticalHit);
+        assert_eq!("direct hit".parse::<AttributeKind>().unwrap(), AttributeKind::DirectHit);
+        assert_eq!(AttributeKind::HealingMagicPotency.to_string(), "Healing Magic Potency");
+        assert!("Critical Hits".parse::<AttributeKind>().is_err());
+    }
+
+    #[test]
+    fn attributes_are_grouped_by_category() {
+        use crate::model::attribute::{Attribute, AttributeCategory, Attributes};
+
+        let mut attribs = Attributes::new();
+        attribs.insert("Strength".into(), Attribute { level: 130 });
+        attribs.insert("Critical Hit Rate".into(), Attribute { level: 2050 });
+        attribs.insert("Determination".into(), Attribute { level: 1014 });
+        attribs.insert_in(AttributeCategory::Role, "Fortitude".into(), Attribute { level: 10 });
+        attribs.insert("Unknown".into(), Attribute { level: 1 });
+
+        let offensive = attribs.offensive().map(|(name, _)| name).collect::<Vec<_>>();
+        assert_eq!(offensive, vec!["Critical Hit Rate", "Determination"]);
+        assert_eq!(attribs.category("Fortitude"), Some(AttributeCategory::Role));
+        assert_eq!(attribs.category("Unknown"), None);
+        assert_eq!("Offensive Properties".parse::<AttributeCategory>().unwrap(), AttributeCategory::Offensive);
+
+        let groups = attribs.grouped().into_iter().map(|(category, _)| category).collect::<Vec<_>>();
+        assert_eq!(groups, vec![AttributeCategory::Attributes, AttributeCategory::Offensive, AttributeCategory::Role]);
+    }
+
+    #[test]
+    fn can_compute_exp_to_next_level() {
+        use crate::model::class::ClassInfo;
+
+        let class = ClassInfo { level: 70, current_xp: Some(6910613), max_xp: Some(12449000) };
+        assert_eq!(class.exp_to_next_level(), Some(5538387));
+        assert_eq!(class.exp_to_next_level_display().unwrap(), "5.5M to next level");
+
+        let class = ClassInfo { level: 6, current_xp: Some(1652), max_xp: Some(4200) };
+        assert_eq!(class.exp_to_next_level_display().unwrap(), "2.5K to next level");
+
+        let class = ClassInfo { level: 70, current_xp: Some(1), max_xp: Some(1_000_000) };
+        assert_eq!(class.exp_to_next_level_display().unwrap(), "1.0M to next level");
+
+        let class = ClassInfo { level: 70, current_xp: Some(51), max_xp: Some(1_000_000) };
+        assert_eq!(class.exp_to_next_level_display().unwrap(), "999.9K to next level");
+
+        let class = ClassInfo { level: 80, current_xp: None, max_xp: None };
+        assert_eq!(class.exp_to_next_level(), None);
+        assert_eq!(class.exp_to_next_level_display(), None);
+        assert_eq!(class.xp_progress(), None);
+        assert!(class.is_max_level(80));
+        assert!(!class.is_max_level(100));
+
+        let class = ClassInfo { level: 50, current_xp: Some(250), max_xp: Some(1000) };
+        assert_eq!(class.xp_progress(), Some(0.25));
+        assert_eq!(class.xp_remaining(), Some(750));
+    }
+
+    #[test]
+    fn limited_jobs_have_their_own_cap() {
+        use crate::model::class::{Classes, ClassInfo, ClassType};
+
+        assert!(ClassType::BlueMage.is_limited_job());
+        assert!(!ClassType::RedMage.is_limited_job());
+        assert_eq!(ClassType::BlueMage.level_cap(), 80);
+        assert_eq!(ClassType::RedMage.level_cap(), 100);
+        assert_eq!("Blue Mage (Limited Job)".parse::<ClassType>().unwrap(), ClassType::BlueMage);
+
+        let mut classes = Classes::new();
+        classes.insert(ClassType::BlueMage, Some(ClassInfo { level: 80, current_xp: None, max_xp: None }));
+        classes.insert(ClassType::RedMage, Some(ClassInfo { level: 90, current_xp: Some(1), max_xp: Some(2) }));
+        let limited = classes.limited_jobs().map(|(kind, _)| kind).collect::<Vec<_>>();
+        assert_eq!(limited, vec![ClassType::BlueMage]);
+    }
+
+    #[test]
+    fn can_summarize_classes() {
+        use crate::model::class::{Classes, ClassInfo, ClassType};
+
+        let info = |level| Some(ClassInfo { level, current_xp: None, max_xp: None });
+        let mut classes = Classes::new();
+        classes.insert(ClassType::Paladin, info(90));
+        classes.insert(ClassType::Gladiator, info(90));
+        classes.insert(ClassType::WhiteMage, info(90));
+        classes.insert(ClassType::Conjurer, info(90));
+        classes.insert(ClassType::Miner, info(50));
+        classes.insert(ClassType::Botanist, None);
+
+        assert_eq!(classes.iter().count(), 5);
+        assert_eq!(classes.total_levels(), 230);
+        assert_eq!(classes.highest_level_job().map(|(kind, _)| kind), Some(ClassType::Paladin));
+
+        let sorted = classes.sorted_by_level().into_iter().map(|(kind, _)| kind).collect::<Vec<_>>();
+        assert_eq!(sorted, vec![ClassType::Paladin, ClassType::WhiteMage, ClassType::Miner]);
+        assert_eq!(Classes::new().highest_level_job(), None);
+    }
+
+    #[test]
+    fn shared_base_class_levels_are_counted_once() {
+        use crate::model::class::{Classes, ClassType};
+        use select::document::Document;
+
+        let job = |name, level| format!(r#"<li>
+            <div class="character__job__level">{}</div>
+            <div class="character__job__name">{}</div>
+            <div class="character__job__exp">-- / --</div>
+        </li>"#, level, name);
+        let html = format!(
+            r#"<div class="character__content"><ul>{}{}{}</ul></div>"#,
+            job("Scholar", 90), job("Summoner", 90), job("Paladin", 80),
+        );
+
+        let classes = Classes::parse(&Document::from(html.as_str())).unwrap();
+        assert_eq!(classes.get(ClassType::Arcanist).unwrap().level, 90);
+        assert_eq!(classes.total_levels(), 170);
+
+        let sorted = classes.sorted_by_level().into_iter().map(|(kind, _)| kind).collect::<Vec<_>>();
+        assert_eq!(sorted, vec![ClassType::Scholar, ClassType::Paladin]);
+    }
+
+    #[test]
+    fn can_parse_class_icons() {
+        use crate::model::class::{Classes, ClassType};
+        use select::document::Document;
+
+        let html = r#"<div class="character__content"><ul><li>
+            <i class="character__job__icon"><img src="https://img.finalfantasyxiv.com/blu.png"></i>
+            <div class="character__job__level">80</div>
+            <div class="character__job__name">Blue Mage (Limited Job)</div>
+            <div class="character__job__exp">-- / --</div>
+        </li></ul></div>"#;
+
+        let classes = Classes::parse(&Document::from(html)).unwrap();
+        assert_eq!(classes.icon(ClassType::BlueMage), Some("https://img.finalfantasyxiv.com/blu.png"));
+        assert_eq!(classes.get(ClassType::BlueMage).unwrap().level, 80);
+        assert_eq!(classes.icon(ClassType::RedMage), None);
+    }
+
+    #[test]
+    fn can_parse_datacenter_aliases() {
+        use crate::model::datacenter::Datacenter;
+        use std::str::FromStr;
+
+        assert_eq!(Datacenter::from_str("primal").unwrap(), Datacenter::Primal);
+        assert_eq!(Datacenter::from_str("[Primal]").unwrap(), Datacenter::Primal);
+        assert_eq!(Datacenter::from_str(" Primal DC ").unwrap(), Datacenter::Primal);
+        assert_eq!(Datacenter::from_str("Chaos Data Center").unwrap(), Datacenter::Chaos);
+        assert_eq!(Datacenter::from_str("ガイア").unwrap(), Datacenter::Gaia);
+        assert!(Datacenter::from_str("DC").is_err());
+        assert!(Datacenter::from_str("Famfrit").is_err());
+    }
+
+    #[test]
+    fn search_builder_is_reusable() {
+        use crate::model::datacenter::Datacenter;
+        use crate::model::server::Server;
+        use crate::search::SearchBuilder;
+
+        let template = SearchBuilder::new().character("Strawberry Custard");
+        let primal = template.clone().datacenter(Datacenter::Primal);
+        let famfrit = template.clone().server(Server::Famfrit);
+
+        assert_eq!(template.build_url(1), "https://na.finalfantasyxiv.com/lodestone/character/?q=Strawberry+Custard");
+        assert_eq!(primal.build_url(1), "https://na.finalfantasyxiv.com/lodestone/character/?q=Strawberry+Custard&worldname=_dc_Primal");
+        assert_eq!(famfrit.build_url(2), "https://na.finalfantasyxiv.com/lodestone/character/?q=Strawberry+Custard&worldname=Famfrit&page=2");
+
+        let mut search = template;
+        search.set_server(Server::Famfrit).set_datacenter(Datacenter::Primal);
+        assert_eq!(search.build_url(1), primal.build_url(1));
+    }
+
+    #[test]
+    fn search_filters_are_ordered() {
+        use crate::model::gc::GrandCompany;
+        use crate::model::language::Language;
+        use crate::search::SearchBuilder;
+
+        let search = SearchBuilder::new()
+            .lang(Language::French)
+            .lang(Language::English)
+            .grand_company(GrandCompany::ImmortalFlames)
+            .grand_company(GrandCompany::Maelstrom);
+
+        assert_eq!(
+            search.build_url(1),
+            "https://na.finalfantasyxiv.com/lodestone/character/?blog_lang=en&blog_lang=fr&gcid=1&gcid=3"
+        );
+    }
+
+    #[test]
+    fn search_queries_are_encoded() {
+        use crate::search::SearchBuilder;
+
+        assert_eq!(
+            SearchBuilder::new().character("L'thani Ze'ka").build_url(1),
+            "https://na.finalfantasyxiv.com/lodestone/character/?q=L%27thani+Ze%27ka"
+        );
+        assert_eq!(
+            SearchBuilder::new().character("Zoë R&D").build_url(1),
+            "https://na.finalfantasyxiv.com/lodestone/character/?q=Zo%C3%AB+R%26D"
+        );
+        assert_eq!(SearchBuilder::new().build_url(1), "https://na.finalfantasyxiv.com/lodestone/character/");
+    }
+
+    #[test]
+    fn can_search_linkshells() {
+        use crate::model::{datacenter::Datacenter, server::Server};
+        use crate::search::{LinkshellSearchBuilder, MemberCount, parse_linkshell_results};
+        use select::document::Document;
+
+        let search = LinkshellSearchBuilder::new()
+            .name("Custard Club")
+            .server(Server::Famfrit)
+            .member_count(MemberCount::UpTo50)
+            .member_count(MemberCount::UpTo30);
+        assert_eq!(
+            search.build_url(2),
+            "https://na.finalfantasyxiv.com/lodestone/linkshell/?q=Custard+Club&worldname=Famfrit&character_count=11-30&character_count=31-50&page=2"
+        );
+
+        let html = r#"<div class="ldst__window">
+            <a href="/lodestone/linkshell/19984723346535274/" class="entry__link--line">
+                <p class="entry__name">Custard Club</p>
+                <p class="entry__world">Famfrit&nbsp;[Primal]</p>
+            </a>
+        </div>"#;
+        let results = parse_linkshell_results(&Document::from(html)).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, 19984723346535274);
+        assert_eq!(results[0].name, "Custard Club");
+        assert_eq!(results[0].datacenter, Datacenter::Primal);
+    }
+
+    #[test]
+    fn can_search_crossworld_linkshells() {
+        use crate::model::datacenter::Datacenter;
+        use crate::search::{CrossworldLinkshellSearchBuilder, parse_cwls_results};
+        use select::document::Document;
+
+        let search = CrossworldLinkshellSearchBuilder::new().name("Custard").datacenter(Datacenter::Primal);
+        assert_eq!(
+            search.build_url(1),
+            "https://na.finalfantasyxiv.com/lodestone/crossworld_linkshell/?q=Custard&dcname=Primal"
+        );
+
+        let html = r#"<div class="ldst__window">
+            <a href="/lodestone/crossworld_linkshell/f5f2e9f5c3a1b8d2/" class="entry__link--line">
+                <p class="entry__name">Custard Crossing</p>
+                <p class="entry__world">Primal</p>
+                <div class="entry__linkshell__num">1,024 Members</div>
+            </a>
+            <a href="/lodestone/crossworld_linkshell/abc123/?hl=en" class="entry__link--line">
+                <p class="entry__name">Pudding Crossing</p>
+                <p class="entry__world">Primal</p>
+                <div class="entry__linkshell__num">12 Members</div>
+            </a>
+            <a href="/lodestone/crossworld_linkshell/?q=Custard" class="entry__link--line">
+                <p class="entry__name">Not A Linkshell</p>
+                <p class="entry__world">Primal</p>
+                <div class="entry__linkshell__num">1 Member</div>
+            </a>
+        </div>"#;
+        let results = parse_cwls_results(&Document::from(html)).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[1].id, "abc123");
+        assert_eq!(results[1].member_count, 12);
+        assert_eq!(results[0].id, "f5f2e9f5c3a1b8d2");
+        assert_eq!(results[0].name, "Custard Crossing");
+        assert_eq!(results[0].datacenter, Datacenter::Primal);
+        assert_eq!(results[0].member_count, 1024);
+    }
+
+    #[test]
+    fn grand_company_ids_round_trip() {
+        use crate::model::gc::GrandCompany;
+        use std::str::FromStr;
+
+        for gc in &[GrandCompany::Unaffiliated, GrandCompany::Maelstrom, GrandCompany::TwinAdder, GrandCompany::ImmortalFlames] {
+            assert_eq!(GrandCompany::from_gcid(gc.to_gcid()), Some(*gc));
+            assert_eq!(GrandCompany::from_str(&gc.to_string()).unwrap(), *gc);
+        }
+
+        assert_eq!(GrandCompany::from_gcid(4), None);
+    }
+
+    #[test]
+    fn can_validate_character_names() {
+        use crate::model::name::{validate_name, NameValidationError};
+
+        assert_eq!(validate_name("Strawberry Custard"), Ok(()));
+        assert_eq!(validate_name("L'thani Ze'ka"), Ok(()));
+        assert_eq!(validate_name("Y'shtola Rhul"), Ok(()));
+
+        assert_eq!(validate_name("Strawberry"), Err(NameValidationError::WrongPartCount(1)));
+        assert_eq!(validate_name("Strawberry  Custard"), Err(NameValidationError::WrongPartCount(3)));
+        assert_eq!(validate_name("S Custard"), Err(NameValidationError::PartTooShort("S".into())));
+        assert_eq!(validate_name("Strawberryyyyyyy Custard"), Err(NameValidationError::PartTooLong("Strawberryyyyyyy".into())));
+        assert_eq!(validate_name("Strawberryyyy Custards"), Err(NameValidationError::NameTooLong));
+        assert_eq!(validate_name("Strawberry Cust4rd"), Err(NameValidationError::InvalidCharacter('4')));
+        assert_eq!(validate_name("Zoë Custard"), Err(NameValidationError::InvalidCharacter('ë')));
+        assert_eq!(validate_name("'Straw Custard"), Err(NameValidationError::MisplacedPunctuation("'Straw".into())));
+        assert_eq!(validate_name("Straw--berry Custard"), Err(NameValidationError::MisplacedPunctuation("Straw--berry".into())));
+    }
+
+    #[test]
+    fn can_read_materia_tiers() {
+        use crate::model::equipment::Materia;
+
+        assert_eq!(Materia::parse_tier("Savage Aim Materia X"), Some(10));
+        assert_eq!(Materia::parse_tier("Quickarm Materia IX"), Some(9));
+        assert_eq!(Materia::parse_tier("Heavens' Eye Materia VI"), Some(6));
+        assert_eq!(Materia::parse_tier("Piety Materia IV"), Some(4));
+        assert_eq!(Materia::parse_tier("Crafter's Delineation"), None);
+    }
+
+    #[test]
+    fn can_compute_average_item_level() {
+        use crate::model::equipment::{Equipment, EquippedItem, GearSlot};
+
+        let item = |slot, category: &str, item_level| EquippedItem {
+            slot,
+            name: "Item".into(),
+            category: category.into(),
+            item_level: Some(item_level),
+            materia: Vec::new(),
+            glamour: None,
+        };
+
+        let mut equipment = Equipment::new();
+        assert_eq!(equipment.average_item_level(), None);
+
+        equipment.insert(item(GearSlot::MainHand, "Two-handed Thaumaturge's Arm", 540));
+        for slot in &[GearSlot::Head, GearSlot::Body, GearSlot::Hands, GearSlot::Legs, GearSlot::Feet] {
+            equipment.insert(item(*slot, "Armor", 530));
+        }
+        for slot in &[GearSlot::Earrings, GearSlot::Necklace, GearSlot::Bracelets, GearSlot::Ring1, GearSlot::Ring2] {
+            equipment.insert(item(*slot, "Accessory", 520));
+        }
+        equipment.insert(item(GearSlot::SoulCrystal, "Soul Crystal", 30));
+
+        assert_eq!(equipment.average_item_level(), Some((540 * 2 + 530 * 5 + 520 * 5) / 12));
+
+        equipment.insert(item(GearSlot::MainHand, "Gladiator's Arm", 540));
+        assert_eq!(equipment.average_item_level(), Some((540 + 530 * 5 + 520 * 5) / 12));
+    }
+
+    #[test]
+    fn weapons_without_off_hand_count_twice() {
+        use crate::model::profile::Profile;
+        use select::document::Document;
+
+        let html = r#"<div class="character__detail">
+            <div class="icon-c--0">
+                <p class="db-tooltip__item__name">Skyruin Greataxe</p>
+                <p class="db-tooltip__item__category">Marauder's Arm</p>
+                <div class="db-tooltip__item__level">Item Level 540</div>
+            </div>
+            <div class="icon-c--1"></div>
+            <div class="icon-c--2">
+                <p class="db-tooltip__item__name">Skyruin Helm</p>
+                <p class="db-tooltip__item__category">Head</p>
+                <div class="db-tooltip__item__level">Item Level 530</div>
+            </div>
+        </div>"#;
+
+        let equipment = Profile::parse_equipment(&Document::from(html)).unwrap();
+        assert_eq!(equipment.len(), 2);
+        assert_eq!(equipment.average_item_level(), Some((540 * 2 + 530) / 12));
+    }
+
+    #[test]
+    fn can_parse_blog_list() {
+        use crate::model::blog::parse_blog_list;
+        use select::document::Document;
+
+        let html = r#"<ul>
+            <li class="entry__blog"><a href="/lodestone/character/11908971/blog/4567890/">
+                <div class="entry__blog__img"><img src="https://img.finalfantasyxiv.com/blog/thumb.jpg"></div>
+                <h3 class="entry__blog__title">A day in Limsa</h3>
+                <time class="entry__blog__time"><script>document.write(ldst_strftime(1583034425, 'YMDHM'));</script></time>
+            </a></li>
+            <li class="entry__blog"><a href="/lodestone/character/11908971/blog/4567000/">
+                <h3 class="entry__blog__title"> Hello </h3>
+            </a></li>
+        </ul>"#;
+
+        let entries = parse_blog_list(&Document::from(html)).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].id, 4567890);
+        assert_eq!(entries[0].title, "A day in Limsa");
+        assert_eq!(entries[0].posted_at, Some(1583034425));
+        assert_eq!(entries[0].thumbnail.as_deref(), Some("https://img.finalfantasyxiv.com/blog/thumb.jpg"));
+        assert_eq!(entries[1].title, "Hello");
+        assert_eq!(entries[1].thumbnail, None);
+    }
+
+    #[test]
+    fn can_parse_blog_entry() {
+        use crate::model::blog::BlogEntry;
+        use select::document::Document;
+
+        let html = r#"<article>
+            <h2 class="entry__blog__title">A day in Limsa</h2>
+            <time class="entry__blog__time"><script>document.write(ldst_strftime(1583034425, 'YMDHM'));</script></time>
+            <div class="entry__blog__body"><p>First line<br>Second line</p><p><img src="https://img.finalfantasyxiv.com/blog/1.jpg">Last</p></div>
+            <ul class="entry__blog__tag"><li><a href="/tag/screenshots">Screenshots</a></li><li><a href="/tag/limsa">Limsa</a></li></ul>
+            <span class="entry__blog__like__count">1,024</span>
+            <span class="entry__blog__comment__count">3</span>
+        </article>"#;
+
+        let entry = BlogEntry::parse(4567890, &Document::from(html)).unwrap();
+        assert_eq!(entry.title, "A day in Limsa");
+        assert_eq!(entry.posted_at, Some(1583034425));
+        assert_eq!(entry.body, "First line\nSecond line\nLast");
+        assert_eq!(entry.images, vec!["https://img.finalfantasyxiv.com/blog/1.jpg"]);
+        assert_eq!(entry.tags, vec!["Screenshots", "Limsa"]);
+        assert_eq!(entry.likes, 1024);
+        assert_eq!(entry.comments, 3);
+    }
+
+    #[test]
+    fn fetch_statuses_are_distinguished() {
+        use crate::model::profile::SearchError;
+        use crate::model::util::status_error;
+        use reqwest::StatusCode;
+
+        let kind = |status, body| status_error(status, body).downcast::<SearchError>().unwrap();
+
+        assert!(matches!(kind(StatusCode::NOT_FOUND, ""), SearchError::NotFound));
+        assert!(matches!(kind(StatusCode::TOO_MANY_REQUESTS, ""), SearchError::RateLimited));
+        assert!(matches!(kind(StatusCode::SERVICE_UNAVAILABLE, "The Lodestone is currently undergoing maintenance."), SearchError::Maintenance));
+        assert!(matches!(kind(StatusCode::SERVICE_UNAVAILABLE, ""), SearchError::ServerError(503)));
+        assert!(matches!(kind(StatusCode::BAD_GATEWAY, ""), SearchError::ServerError(502)));
+        assert!(matches!(kind(StatusCode::FORBIDDEN, ""), SearchError::UnexpectedStatus(403)));
+    }
+
+    #[test]
+    fn private_sections_are_reported() {
+        use crate::model::achievement::AchievementSummary;
+        use crate::model::profile::{SearchError, Section};
+        use select::document::Document;
+
+        let html = r#"<div class="ldst__window"><p class="parts__zero">This character's achievements are private.</p></div>"#;
+        let error = AchievementSummary::parse(&Document::from(html)).unwrap_err();
+        assert!(matches!(error.downcast_ref::<SearchError>(), Some(SearchError::SectionPrivate(Section::Achievements))));
+
+        let html = r#"<div class="ldst__window"><p class="parts__zero">No achievements found.</p></div>"#;
+        let error = AchievementSummary::parse(&Document::from(html)).unwrap_err();
+        assert!(!error.to_string().contains("private"));
+    }
+
+    #[test]
+    fn can_parse_character_params() {
+        use crate::model::param::{CharacterParams, SecondaryParam};
+        use select::document::Document;
+
+        let page = |secondary: &str, value: &str| format!(r#"<div class="character__param"><ul>
+            <li><p class="character__param__text character__param__text__hp--en-us">HP</p><span>45835</span></li>
+            <li><p class="character__param__text character__param__text__{}--en-us">{}</p><span>{}</span></li>
+        </ul></div>"#, secondary, secondary.to_uppercase(), value);
+
+        let params = CharacterParams::parse(&Document::from(page("mp", "10000").as_str())).unwrap();
+        assert_eq!(params.hp, 45835);
+        assert_eq!(params.secondary, SecondaryParam::Mp(10000));
+
+        let params = CharacterParams::parse(&Document::from(page("cp", "612").as_str())).unwrap();
+        assert_eq!(params.secondary, SecondaryParam::Cp(612));
+        assert_eq!(params.mp(), None);
+
+        let params = CharacterParams::parse(&Document::from(page("gp", "1,002").as_str())).unwrap();
+        assert_eq!(params.secondary, SecondaryParam::Gp(1002));
+        assert_eq!(params.secondary.value(), 1002);
+
+        assert!(CharacterParams::parse(&Document::from(page("tp", "1000").as_str())).is_err());
+    }
+
+    #[test]
+    fn can_detect_active_class() {
+        use crate::model::class::ClassType;
+        use crate::model::equipment::{Equipment, EquippedItem, GearSlot};
+
+        let item = |slot, name: &str, category: &str| EquippedItem {
+            slot,
+            name: name.into(),
+            category: category.into(),
+            item_level: None,
+            materia: Vec::new(),
+            glamour: None,
+        };
+
+        let mut equipment = Equipment::new();
+        assert_eq!(equipment.active_class(), None);
+
+        equipment.insert(item(GearSlot::MainHand, "Staff", "Two-handed Thaumaturge's Arm"));
+        assert_eq!(equipment.active_class(), Some(ClassType::Thaumaturge));
+
+        equipment.insert(item(GearSlot::SoulCrystal, "Soul of the Black Mage", "Soul Crystal"));
+        assert_eq!(equipment.active_class(), Some(ClassType::BlackMage));
+
+        let mut equipment = Equipment::new();
+        equipment.insert(item(GearSlot::MainHand, "Saw", "Carpenter's Primary Tool"));
+        assert_eq!(equipment.active_class(), Some(ClassType::Carpenter));
+    }
+
+    #[test]
+    fn active_class_is_read_from_the_header_icon() {
+        use crate::model::class::{Classes, ClassType};
+        use crate::model::equipment::{Equipment, EquippedItem, GearSlot};
+        use crate::model::profile::Profile;
+        use select::document::Document;
+
+        let doc = Document::from(r#"<div class="character__class">
+            <div class="character__class_icon"><img src="https://img.finalfantasyxiv.com/lds/h/blm.png"></div>
+            <div class="character__class__data"><p>LEVEL 90</p></div>
+        </div>"#);
+
+        let mut classes = Classes::new();
+        classes.insert_icon(ClassType::BlackMage, "https://img.finalfantasyxiv.com/lds/h/blm.png".into());
+        classes.insert_icon(ClassType::Paladin, "https://img.finalfantasyxiv.com/lds/h/pld.png".into());
+
+        //  The icon wins even when nothing is in the main hand.
+        let empty = Equipment::new();
+        assert_eq!(Profile::parse_active_class(&doc, Some(&classes), &empty), Some(ClassType::BlackMage));
+
+        //  Without the class page, the gear is used instead.
+        let mut equipment = Equipment::new();
+        equipment.insert(EquippedItem {
+            slot: GearSlot::MainHand,
+            name: "Staff".into(),
+            category: "Two-handed Thaumaturge's Arm".into(),
+            item_level: None,
+            materia: Vec::new(),
+            glamour: None,
+        });
+        assert_eq!(Profile::parse_active_class(&doc, None, &equipment), Some(ClassType::Thaumaturge));
+        assert_eq!(Profile::parse_active_class(&doc, None, &empty), None);
+    }
+
+    #[test]
+    fn can_aggregate_profiles() {
+        use crate::analysis;
+        use crate::model::{clan::Clan, class::{ClassType, Role}, profile::Profile, race::Race};
+
+        let profile = |race: &str, weapon: Option<(&str, u32)>, jobs: &[(&str, u32)]| {
+            let gear = weapon.map(|(category, item_level)| format!(r#"<div class="icon-c--0">
+                <p class="db-tooltip__item__name">Weapon</p>
+                <p class="db-tooltip__item__category">{}</p>
+                <div class="db-tooltip__item__level">Item Level {}</div>
+            </div>"#, category, item_level)).unwrap_or_default();
+
+            let main = format!(r#"<div class="ldst__window">
+                <p class="frame__chara__name">Strawberry Custard</p>
+                <p class="frame__chara__world">Famfrit&nbsp;[Primal]</p>
+                <p class="character-block__name">{}</p>
+                <p class="character-block__birth">1st Sun of the 1st Astral Moon</p>
+                <p class="character-block__name">Halone, the Fury</p>
+                <p class="character-block__name">Limsa Lominsa</p>
+                <div class="character__param"><ul>
+                    <li><p class="character__param__text__hp--en-us">HP</p><span>1000</span></li>
+                    <li><p class="character__param__text__mp--en-us">MP</p><span>10000</span></li>
+                </ul></div>
+                <div class="character__profile__data"></div>
+                {}
+            </div>"#, race, gear);
+
+            let classes = jobs.iter()
+                .map(|(name, level)| format!(r#"<li>
+                    <div class="character__job__level">{}</div>
+                    <div class="character__job__name">{}</div>
+                    <div class="character__job__exp">-- / --</div>
+                </li>"#, level, name))
+                .collect::<String>();
+            let classes = format!(r#"<div class="character__content"><ul>{}</ul></div>"#, classes);
+
+            Profile::parse_from_html(11908971, &main, &classes).unwrap()
+        };
+
+        let profiles = vec![
+            profile("Miqo'te<br>Seeker of the Sun / ♀", Some(("Two-handed Thaumaturge's Arm", 540)), &[("White Mage", 90), ("Warrior", 80)]),
+            profile("Miqo'te<br>Keeper of the Moon / ♂", Some(("Gladiator's Arm", 600)), &[("Warrior", 90)]),
+            profile("Lalafell<br>Plainsfolk / ♀", None, &[("Black Mage", 50), ("Warrior", 40)]),
+        ];
+
+        let levels = analysis::job_level_distribution(&profiles);
+        let warrior = levels[&ClassType::Warrior];
+        assert_eq!(warrior.count, 3);
+        assert_eq!(warrior.min, 40);
+        assert_eq!(warrior.max, 90);
+        assert_eq!(warrior.average, 70.0);
+        assert_eq!(levels[&ClassType::Marauder], warrior);
+        assert_eq!(levels[&ClassType::BlackMage].count, 1);
+        assert!(!levels.contains_key(&ClassType::Paladin));
+
+        //  Every role is the most played once, so they are ordered as in `Role`.
+        assert_eq!(analysis::most_played_roles(&profiles), vec![(Role::Tank, 1), (Role::Healer, 1), (Role::Damage, 1)]);
+
+        let races = analysis::race_breakdown(&profiles);
+        assert_eq!(races.len(), 2);
+        assert_eq!(races[&Race::Miqote], 2);
+        assert_eq!(races[&Race::Lalafell], 1);
+
+        let clans = analysis::clan_breakdown(&profiles);
+        assert_eq!(clans.len(), 3);
+        assert_eq!(clans[&Clan::SeekerOfTheSun], 1);
+        assert_eq!(clans[&Clan::KeeperOfTheMoon], 1);
+        assert_eq!(clans[&Clan::Plainsfolk], 1);
+
+        //  The Thaumaturge's Arm counts twice and the Gladiator's Arm once: (90 + 50) / 2.
+        assert_eq!(analysis::average_item_level(&profiles), Some(70.0));
+        assert_eq!(analysis::average_item_level(&profiles[2..]), None);
+        assert_eq!(analysis::average_item_level(&[]), None);
+    }
+
+    #[test]
+    fn can_parse_achievement_entries() {
+        use crate::model::achievement::{parse_achievement_list, AchievementSummary};
+        use select::document::Document;
+
+        let doc = Document::from(r#"
+            <p class="achievement__point">1,510</p>
+            <p class="parts__total">153 Total</p>
+            <ul>
+                <li class="entry">
+                    <a href="/lodestone/character/11908971/achievement/detail/2179/" class="entry__achievement">
+                        <div class="entry__activity">
+                            <p class="entry__activity__txt">Strawberry Custard earned the achievement "To Crush Your Enemies IV"!</p>
+                            <time class="entry__activity__time"><span id="datetime-1">-</span><script>document.getElementById('datetime-1').innerHTML = ldst_strftime(1583034425, 'YMD');</script></time>
+                        </div>
+                        <p class="entry__achievement__number">10</p>
+                    </a>
+                </li>
+            </ul>"#);
+
+        let summary = AchievementSummary::parse(&doc).unwrap();
+        assert_eq!(summary.points, 1510);
+        assert_eq!(summary.total, Some(153));
+        assert_eq!(summary.recent.len(), 1);
+
+        let achievements = parse_achievement_list(&doc).unwrap();
+        assert_eq!(achievements.len(), 1);
+        assert_eq!(achievements[0].id, 2179);
+        assert_eq!(achievements[0].name, "To Crush Your Enemies IV");
+        assert_eq!(achievements[0].points, 10);
+        assert_eq!(achievements[0].earned_at, Some(1583034425));
+    }
+
     #[test]
     fn profile_is_correct() {
         use crate::model::{
+            attribute::AttributeKind,
             clan::Clan,
             class::ClassType,
             datacenter::Datacenter,
@@ -64,43 +1193,51 @@ mod tests {
         assert_eq!(strawberry.nameday, "3rd Sun of the 1st Umbral Moon");
         assert_eq!(strawberry.guardian, "Halone, the Fury");
         assert_eq!(strawberry.city_state, "Limsa Lominsa");
+        assert_eq!(strawberry.datacenter, Datacenter::Primal);
 
         assert_eq!(strawberry.race, Race::Lalafell);
         assert_eq!(strawberry.clan, Clan::Plainsfolk);
         assert_eq!(strawberry.gender, Gender::Female);
         assert_eq!(strawberry.level(ClassType::BlackMage), Some(70));
+        assert!(strawberry.has_unlocked(ClassType::Gladiator));
+        assert!(!strawberry.has_unlocked(ClassType::Paladin));
+        assert!(!strawberry.has_unlocked(ClassType::BlueMage));
 
-        assert_eq!(strawberry.hp, 45835);
-        assert_eq!(strawberry.mp, 10000);
+        assert_eq!(strawberry.params.hp, 45835);
+        assert_eq!(strawberry.params.mp(), Some(10000));
 
         let attribs = &strawberry.attributes;
 
-        assert_eq!(attribs.get("Strength").unwrap().level, 130);
-        assert_eq!(attribs.get("Dexterity").unwrap().level, 295);
-        assert_eq!(attribs.get("Vitality").unwrap().level, 2937);
-        assert_eq!(attribs.get("Intelligence").unwrap().level, 3031);
-        assert_eq!(attribs.get("Mind").unwrap().level, 219);
+        assert_eq!(attribs.get(AttributeKind::Strength).unwrap().level, 130);
+        assert_eq!(attribs.get(AttributeKind::Dexterity).unwrap().level, 295);
+        assert_eq!(attribs.get(AttributeKind::Vitality).unwrap().level, 2937);
+        assert_eq!(attribs.get(AttributeKind::Intelligence).unwrap().level, 3031);
+        assert_eq!(attribs.get(AttributeKind::Mind).unwrap().level, 219);
         
-        assert_eq!(attribs.get("Critical Hit Rate").unwrap().level, 2050);
-        assert_eq!(attribs.get("Determination").unwrap().level, 1014);
-        assert_eq!(attribs.get("Direct Hit Rate").unwrap().level, 1183);
+        assert_eq!(attribs.get(AttributeKind::CriticalHit).unwrap().level, 2050);
+        assert_eq!(attribs.get(AttributeKind::Determination).unwrap().level, 1014);
+        assert_eq!(attribs.get(AttributeKind::DirectHit).unwrap().level, 1183);
         
-        assert_eq!(attribs.get("Defense").unwrap().level, 2238);
-        assert_eq!(attribs.get("Magic Defense").unwrap().level, 3912);
+        assert_eq!(attribs.get(AttributeKind::Defense).unwrap().level, 2238);
+        assert_eq!(attribs.get(AttributeKind::MagicDefense).unwrap().level, 3912);
         
-        assert_eq!(attribs.get("Attack Power").unwrap().level, 130);
-        assert_eq!(attribs.get("Skill Speed").unwrap().level, 364);
+        assert_eq!(attribs.get(AttributeKind::AttackPower).unwrap().level, 130);
+        assert_eq!(attribs.get(AttributeKind::SkillSpeed).unwrap().level, 364);
         
-        assert_eq!(attribs.get("Attack Magic Potency").unwrap().level, 3031);
-        assert_eq!(attribs.get("Healing Magic Potency").unwrap().level, 219);
-        assert_eq!(attribs.get("Spell Speed").unwrap().level, 1856);
+        assert_eq!(attribs.get(AttributeKind::AttackMagicPotency).unwrap().level, 3031);
+        assert_eq!(attribs.get(AttributeKind::HealingMagicPotency).unwrap().level, 219);
+        assert_eq!(attribs.get(AttributeKind::SpellSpeed).unwrap().level, 1856);
         
-        assert_eq!(attribs.get("Tenacity").unwrap().level, 364);
-        assert_eq!(attribs.get("Piety").unwrap().level, 292);
+        assert_eq!(attribs.get(AttributeKind::Tenacity).unwrap().level, 364);
+        assert_eq!(attribs.get(AttributeKind::Piety).unwrap().level, 292);
+
+        assert_eq!(attribs.get_raw("Direct Hit").unwrap().level, 1183);
+        assert_eq!(attribs.get_raw("Critical Hit").unwrap().level, 2050);
 
-        assert_eq!(attribs.get("Invalid Attribute"), None);
+        assert_eq!(attribs.get_raw("Invalid Attribute"), None);
+        assert_eq!(attribs.offensive().count(), 3);
 
-        let classes = &strawberry.all_class_info();
+        let classes = strawberry.all_class_info().unwrap();
 
         assert_eq!(classes.get(ClassType::Paladin), None);
         let mut class = classes.get(ClassType::Gladiator).unwrap();
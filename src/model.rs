@@ -1,11 +1,30 @@
+pub mod achievement;
 pub mod attribute;
+pub mod blog;
 pub mod clan;
 pub mod class;
+#[cfg(feature = "image")]
+pub mod crest;
 pub mod datacenter;
+pub mod equipment;
+pub mod free_company;
 pub mod gc;
 pub mod gender;
+pub mod id;
+pub mod images;
 pub mod language;
+pub mod linkshell;
+pub mod member;
+pub mod minion;
+pub mod mount;
+pub mod name;
+pub mod param;
 pub mod profile;
+pub mod pvp_team;
 pub mod race;
+pub mod resource;
+pub mod roster;
 pub mod server;
+pub mod stats;
+pub mod title;
 pub(crate) mod util;
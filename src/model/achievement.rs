@@ -0,0 +1,237 @@
+use failure::Error;
+use select::document::Document;
+use select::predicate::{Class, Name};
+
+use crate::diagnostics::parse_or_dump;
+use crate::model::profile::Section;
+use crate::model::util::{BASE_PROFILE_URL, ensure_node, ensure_public, fetch_html, parse_strftime_timestamp};
+use crate::pagination::{Page, Paginated, Pager, parse_page_numbers};
+
+/// An achievement a character has earned.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Achievement {
+    /// The lodestone's id for the achievement.
+    pub id: u32,
+    /// The achievement's name.
+    pub name: String,
+    /// How many achievement points it is worth.
+    pub points: u32,
+    /// When it was earned, as a Unix timestamp.
+    pub earned_at: Option<u64>,
+}
+
+/// Identifies an achievement either by its lodestone id or by its name.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum AchievementKey<'a> {
+    Id(u32),
+    /// Names are compared case insensitively.
+    Name(&'a str),
+}
+
+impl<'a> AchievementKey<'a> {
+    fn matches(&self, achievement: &Achievement) -> bool {
+        match *self {
+            AchievementKey::Id(id) => achievement.id == id,
+            AchievementKey::Name(name) => achievement.name.eq_ignore_ascii_case(name),
+        }
+    }
+}
+
+impl<'a> From<u32> for AchievementKey<'a> {
+    fn from(id: u32) -> Self {
+        AchievementKey::Id(id)
+    }
+}
+
+impl<'a> From<&'a str> for AchievementKey<'a> {
+    fn from(name: &'a str) -> Self {
+        AchievementKey::Name(name)
+    }
+}
+
+/// An overview of a character's achievements that only needs one request.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AchievementSummary {
+    /// The character's total achievement points.
+    pub points: u32,
+    /// How many achievements the character has earned, if shown.
+    pub total: Option<u32>,
+    /// The most recently earned achievements, most recent first.
+    pub recent: Vec<Achievement>,
+}
+
+impl AchievementSummary {
+    /// Gets the achievement summary for a user given their lodestone user id.
+    pub fn get(user_id: u32) -> Result<Self, Error> {
+        let html = fetch_html(&format!("{}{}/achievement/", BASE_PROFILE_URL, user_id))?;
+        parse_or_dump(&format!("achievements-{}", user_id), &html, Self::parse)
+    }
+
+    pub(crate) fn parse(doc: &Document) -> Result<Self, Error> {
+        ensure_public(doc, Section::Achievements)?;
+        let points = ensure_node!(doc, Class("achievement__point"))
+            .text()
+            .trim()
+            .replace(",", "")
+            .parse::<u32>()?;
+
+        let total = doc.find(Class("parts__total"))
+            .next()
+            .and_then(|node| node
+                .text()
+                .replace(",", "")
+                .split(|ch: char| !ch.is_ascii_digit())
+                .find_map(|part| part.parse::<u32>().ok()));
+
+        Ok(AchievementSummary {
+            points,
+            total,
+            recent: parse_achievement_list(doc)?,
+        })
+    }
+}
+
+/// Every achievement a character has earned, most recent first.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Achievements(Vec<Achievement>);
+
+impl Achievements {
+    /// Gets all achievements for a user given their lodestone user id,
+    /// walking through every page of their achievement list.
+    ///
+    /// Characters can have thousands of achievements spread over dozens of
+    /// pages. Use `pages` instead to stop once enough have been read.
+    pub fn get(user_id: u32) -> Result<Self, Error> {
+        let mut achievements = Vec::new();
+
+        for page in Self::pages(user_id) {
+            achievements.extend(page?);
+        }
+
+        Ok(Achievements(achievements))
+    }
+
+    /// Creates a `Pager` over a user's achievement list, which fetches
+    /// one page of achievements at a time as they are requested.
+    pub fn pages(user_id: u32) -> Pager<AchievementPages> {
+        Pager::new(AchievementPages { user_id })
+    }
+
+    /// Searches a user's achievement list for a single achievement, given
+    /// either its id or its name. Pages are fetched one at a time, and the
+    /// search stops as soon as the achievement is found.
+    pub fn find<'a, K: Into<AchievementKey<'a>>>(user_id: u32, key: K) -> Result<Option<Achievement>, Error> {
+        let key = key.into();
+
+        for page in Self::pages(user_id) {
+            if let Some(found) = page?.into_iter().find(|achievement| key.matches(achievement)) {
+                return Ok(Some(found));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Whether an achievement has been earned, given either its id or its name.
+    pub fn has<'a, K: Into<AchievementKey<'a>>>(&self, key: K) -> bool {
+        let key = key.into();
+        self.0.iter().any(|achievement| key.matches(achievement))
+    }
+
+    /// Iterates over the achievements.
+    pub fn iter(&self) -> impl Iterator<Item = &Achievement> {
+        self.0.iter()
+    }
+
+    /// The number of achievements earned.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether no achievements have been earned.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The sum of the points of every achievement held.
+    pub fn points(&self) -> u32 {
+        self.0.iter().map(|achievement| achievement.points).sum()
+    }
+}
+
+impl IntoIterator for Achievements {
+    type Item = Achievement;
+    type IntoIter = std::vec::IntoIter<Achievement>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+/// The pages of a character's achievement list.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct AchievementPages {
+    user_id: u32,
+}
+
+impl Paginated for AchievementPages {
+    type Item = Achievement;
+
+    fn fetch_page(&self, page: u32) -> Result<Page<Achievement>, Error> {
+        let url = format!("{}{}/achievement/?page={}", BASE_PROFILE_URL, self.user_id, page);
+        let html = fetch_html(&url)?;
+
+        parse_or_dump(&format!("achievements-{}-page-{}", self.user_id, page), &html, |doc| {
+            ensure_public(doc, Section::Achievements)?;
+
+            let (current_page, total_pages) = parse_page_numbers(doc);
+
+            Ok(Page {
+                items: parse_achievement_list(doc)?,
+                current_page,
+                total_pages,
+            })
+        })
+    }
+}
+
+/// Parses the entries of an achievement list page.
+pub(crate) fn parse_achievement_list(doc: &Document) -> Result<Vec<Achievement>, Error> {
+    let mut achievements = Vec::new();
+
+    for entry in doc.find(Class("entry__achievement")) {
+        let href = entry.attr("href").unwrap_or_default();
+        let id = href
+            .trim_end_matches('/')
+            .rsplit('/')
+            .next()
+            .unwrap_or_default()
+            .parse::<u32>()?;
+
+        //  The text reads: Name earned the achievement "Achievement Name"!
+        let text = ensure_node!(entry, Class("entry__activity__txt")).text();
+        let name = match (text.find('"'), text.rfind('"')) {
+            (Some(start), Some(end)) if start < end => text[start + 1..end].to_string(),
+            _ => text.trim().to_string(),
+        };
+
+        let points = match entry.find(Class("entry__achievement__number")).next() {
+            Some(node) => node.text().trim().parse::<u32>()?,
+            None => 0,
+        };
+
+        let earned_at = entry.find(Class("entry__activity__time"))
+            .next()
+            .and_then(|time| time.find(Name("script")).next())
+            .and_then(|script| parse_strftime_timestamp(&script.text()));
+
+        achievements.push(Achievement {
+            id,
+            name,
+            points,
+            earned_at,
+        });
+    }
+
+    Ok(achievements)
+}
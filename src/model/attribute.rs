@@ -1,4 +1,15 @@
+use failure::Fail;
 use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Debug, Fail)]
+#[fail(display = "Invalid attribute '{}'", _0)]
+pub struct AttributeKindParseError(String);
+
+#[derive(Clone, Debug, Fail)]
+#[fail(display = "Invalid attribute category '{}'", _0)]
+pub struct AttributeCategoryParseError(String);
 
 /// Contains all data about an attribute; currently, this only consists of the attribute's level
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
@@ -7,22 +18,363 @@ pub struct Attribute {
     pub level: u16
 }
 
+/// Maps attribute names the lodestone has used in the past, or uses
+/// elsewhere, onto the name currently shown on profiles.
+static ATTRIBUTE_ALIASES: &[(&str, &str)] = &[
+    ("Critical Hit", "Critical Hit Rate"),
+    ("Direct Hit", "Direct Hit Rate"),
+];
+
+/// Gets the canonical name for an attribute, so that renamed
+/// attributes resolve to the same key.
+pub fn canonical_name(name: &str) -> &str {
+    ATTRIBUTE_ALIASES.iter()
+        .find(|(alias, _)| *alias == name)
+        .map(|(_, canonical)| *canonical)
+        .unwrap_or(name)
+}
+
+/// The attributes shown on a profile.
+///
+/// Use these with `Attributes::get` rather than spelling out the
+/// lodestone's names; `Attributes::get_raw` remains for anything
+/// not covered here.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum AttributeKind {
+    //  Attributes
+    Strength,
+    Dexterity,
+    Vitality,
+    Intelligence,
+    Mind,
+    //  Offensive Properties
+    CriticalHit,
+    Determination,
+    DirectHit,
+    //  Defensive Properties
+    Defense,
+    MagicDefense,
+    //  Physical Properties
+    AttackPower,
+    SkillSpeed,
+    //  Mental Properties
+    AttackMagicPotency,
+    HealingMagicPotency,
+    SpellSpeed,
+    //  Role
+    Tenacity,
+    Piety,
+    //  Crafting
+    Craftsmanship,
+    Control,
+    //  Gathering
+    Gathering,
+    Perception,
+}
+
+/// The sections the attributes table on a profile is split into.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum AttributeCategory {
+    Attributes,
+    Offensive,
+    Defensive,
+    Physical,
+    Mental,
+    Role,
+    Crafting,
+    Gathering,
+}
+
+/// Converts a section header from the attributes table, e.g.
+/// `Offensive Properties`, into an AttributeCategory.
+/// The conversion is case insensitive.
+impl FromStr for AttributeCategory {
+    type Err = AttributeCategoryParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let upper = s.trim().to_uppercase();
+        match upper.strip_suffix(" PROPERTIES").unwrap_or(&upper) {
+            "ATTRIBUTES" => Ok(AttributeCategory::Attributes),
+            "OFFENSIVE" => Ok(AttributeCategory::Offensive),
+            "DEFENSIVE" => Ok(AttributeCategory::Defensive),
+            "PHYSICAL" => Ok(AttributeCategory::Physical),
+            "MENTAL" => Ok(AttributeCategory::Mental),
+            "ROLE" => Ok(AttributeCategory::Role),
+            "CRAFTING" => Ok(AttributeCategory::Crafting),
+            "GATHERING" => Ok(AttributeCategory::Gathering),
+            _ => Err(AttributeCategoryParseError(s.into())),
+        }
+    }
+}
+
+impl fmt::Display for AttributeCategory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            AttributeCategory::Attributes => "Attributes",
+            AttributeCategory::Offensive => "Offensive Properties",
+            AttributeCategory::Defensive => "Defensive Properties",
+            AttributeCategory::Physical => "Physical Properties",
+            AttributeCategory::Mental => "Mental Properties",
+            AttributeCategory::Role => "Role",
+            AttributeCategory::Crafting => "Crafting",
+            AttributeCategory::Gathering => "Gathering",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl AttributeKind {
+    /// The section of the attributes table this attribute is listed under.
+    pub fn category(self) -> AttributeCategory {
+        match self {
+            AttributeKind::Strength | AttributeKind::Dexterity | AttributeKind::Vitality
+            | AttributeKind::Intelligence | AttributeKind::Mind => AttributeCategory::Attributes,
+            AttributeKind::CriticalHit | AttributeKind::Determination
+            | AttributeKind::DirectHit => AttributeCategory::Offensive,
+            AttributeKind::Defense | AttributeKind::MagicDefense => AttributeCategory::Defensive,
+            AttributeKind::AttackPower | AttributeKind::SkillSpeed => AttributeCategory::Physical,
+            AttributeKind::AttackMagicPotency | AttributeKind::HealingMagicPotency
+            | AttributeKind::SpellSpeed => AttributeCategory::Mental,
+            AttributeKind::Tenacity | AttributeKind::Piety => AttributeCategory::Role,
+            AttributeKind::Craftsmanship | AttributeKind::Control => AttributeCategory::Crafting,
+            AttributeKind::Gathering | AttributeKind::Perception => AttributeCategory::Gathering,
+        }
+    }
+
+    /// The name the lodestone uses for this attribute on profiles.
+    pub fn name(self) -> &'static str {
+        match self {
+            AttributeKind::Strength => "Strength",
+            AttributeKind::Dexterity => "Dexterity",
+            AttributeKind::Vitality => "Vitality",
+            AttributeKind::Intelligence => "Intelligence",
+            AttributeKind::Mind => "Mind",
+            AttributeKind::CriticalHit => "Critical Hit Rate",
+            AttributeKind::Determination => "Determination",
+            AttributeKind::DirectHit => "Direct Hit Rate",
+            AttributeKind::Defense => "Defense",
+            AttributeKind::MagicDefense => "Magic Defense",
+            AttributeKind::AttackPower => "Attack Power",
+            AttributeKind::SkillSpeed => "Skill Speed",
+            AttributeKind::AttackMagicPotency => "Attack Magic Potency",
+            AttributeKind::HealingMagicPotency => "Healing Magic Potency",
+            AttributeKind::SpellSpeed => "Spell Speed",
+            AttributeKind::Tenacity => "Tenacity",
+            AttributeKind::Piety => "Piety",
+            AttributeKind::Craftsmanship => "Craftsmanship",
+            AttributeKind::Control => "Control",
+            AttributeKind::Gathering => "Gathering",
+            AttributeKind::Perception => "Perception",
+        }
+    }
+}
+
+/// Converts a lodestone attribute name, or one of its known aliases,
+/// into an AttributeKind. The conversion is case insensitive.
+impl FromStr for AttributeKind {
+    type Err = AttributeKindParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match &*canonical_name(s.trim()).to_uppercase() {
+            "STRENGTH" => Ok(AttributeKind::Strength),
+            "DEXTERITY" => Ok(AttributeKind::Dexterity),
+            "VITALITY" => Ok(AttributeKind::Vitality),
+            "INTELLIGENCE" => Ok(AttributeKind::Intelligence),
+            "MIND" => Ok(AttributeKind::Mind),
+            "CRITICAL HIT RATE" | "CRITICAL HIT" => Ok(AttributeKind::CriticalHit),
+            "DETERMINATION" => Ok(AttributeKind::Determination),
+            "DIRECT HIT RATE" | "DIRECT HIT" => Ok(AttributeKind::DirectHit),
+            "DEFENSE" => Ok(AttributeKind::Defense),
+            "MAGIC DEFENSE" => Ok(AttributeKind::MagicDefense),
+            "ATTACK POWER" => Ok(AttributeKind::AttackPower),
+            "SKILL SPEED" => Ok(AttributeKind::SkillSpeed),
+            "ATTACK MAGIC POTENCY" => Ok(AttributeKind::AttackMagicPotency),
+            "HEALING MAGIC POTENCY" => Ok(AttributeKind::HealingMagicPotency),
+            "SPELL SPEED" => Ok(AttributeKind::SpellSpeed),
+            "TENACITY" => Ok(AttributeKind::Tenacity),
+            "PIETY" => Ok(AttributeKind::Piety),
+            "CRAFTSMANSHIP" => Ok(AttributeKind::Craftsmanship),
+            "CONTROL" => Ok(AttributeKind::Control),
+            "GATHERING" => Ok(AttributeKind::Gathering),
+            "PERCEPTION" => Ok(AttributeKind::Perception),
+            x => Err(AttributeKindParseError(x.into())),
+        }
+    }
+}
+
+impl fmt::Display for AttributeKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
 /// Holds information about a profiles level in a particular class.
+///
+/// Attributes are kept in the order they were inserted, which for
+/// parsed profiles is the order they are displayed on the lodestone.
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
-pub struct Attributes(HashMap<String, Attribute>);
+pub struct Attributes {
+    entries: Vec<(String, Attribute)>,
+    categories: HashMap<String, AttributeCategory>,
+}
 
 impl Attributes {
     pub fn new() -> Self {
-        Self(HashMap::with_capacity(18))
+        Self {
+            entries: Vec::with_capacity(18),
+            categories: HashMap::new(),
+        }
     }
     /// Adds or updates a given entry.
+    ///
+    /// Known aliases are stored under their canonical name, and
+    /// attributes with an `AttributeKind` are filed under its category.
     pub fn insert(&mut self, name: String, value: Attribute) {
-        self.0.insert(name, value);
+        let category = name.parse::<AttributeKind>()
+            .ok()
+            .map(AttributeKind::category);
+        self.insert_entry(name, value, category);
+    }
+
+    /// Adds or updates a given entry under the section it was listed in.
+    pub fn insert_in(&mut self, category: AttributeCategory, name: String, value: Attribute) {
+        self.insert_entry(name, value, Some(category));
+    }
+
+    fn insert_entry(&mut self, name: String, value: Attribute, category: Option<AttributeCategory>) {
+        let name = match canonical_name(&name) {
+            canonical if canonical != name => canonical.to_string(),
+            _ => name,
+        };
+
+        if let Some(category) = category {
+            self.categories.insert(name.clone(), category);
+        }
+
+        match self.entries.iter_mut().find(|(key, _)| *key == name) {
+            Some((_, attribute)) => *attribute = value,
+            None => self.entries.push((name, value)),
+        }
+    }
+
+    /// Borrows an attribute, if found.
+    pub fn get(&self, kind: AttributeKind) -> Option<&Attribute> {
+        self.get_raw(kind.name())
     }
 
-    /// Borrows an attribute by name, if found
-    pub fn get(&self, name: &str) -> Option<&Attribute> {
-        self.0.get(name)
+    /// Borrows an attribute by the name shown on the lodestone, if found.
+    /// Known aliases such as "Direct Hit" find the same attribute as
+    /// "Direct Hit Rate".
+    ///
+    /// Prefer `get` unless the attribute has no `AttributeKind`.
+    pub fn get_raw(&self, name: &str) -> Option<&Attribute> {
+        let name = canonical_name(name);
+        self.entries.iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, attribute)| attribute)
+    }
+
+    /// The section an attribute was listed under, if known.
+    pub fn category(&self, name: &str) -> Option<AttributeCategory> {
+        self.categories.get(canonical_name(name)).copied()
+    }
+
+    /// The number of attributes held.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether there are no attributes at all.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterates over the attributes and their names in display order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Attribute)> {
+        self.entries.iter().map(|(name, attribute)| (name.as_str(), attribute))
+    }
+
+    /// Iterates over the attributes listed under a given section, in display order.
+    pub fn in_category(&self, category: AttributeCategory) -> impl Iterator<Item = (&str, &Attribute)> {
+        self.iter()
+            .filter(move |(name, _)| self.categories.get(*name) == Some(&category))
+    }
+
+    /// The attributes grouped by section, with both the sections and the
+    /// attributes within them in the order they are displayed.
+    ///
+    /// Attributes whose section is unknown are left out.
+    pub fn grouped(&self) -> Vec<(AttributeCategory, Vec<(&str, &Attribute)>)> {
+        let mut groups: Vec<(AttributeCategory, Vec<(&str, &Attribute)>)> = Vec::new();
+        for (name, attribute) in self.iter() {
+            let category = match self.categories.get(name) {
+                Some(category) => *category,
+                None => continue,
+            };
+
+            match groups.iter_mut().find(|(group, _)| *group == category) {
+                Some((_, entries)) => entries.push((name, attribute)),
+                None => groups.push((category, vec![(name, attribute)])),
+            }
+        }
+        groups
+    }
+
+    /// The main attributes, such as Strength and Vitality.
+    pub fn base(&self) -> impl Iterator<Item = (&str, &Attribute)> {
+        self.in_category(AttributeCategory::Attributes)
+    }
+
+    /// The offensive properties, such as Critical Hit Rate.
+    pub fn offensive(&self) -> impl Iterator<Item = (&str, &Attribute)> {
+        self.in_category(AttributeCategory::Offensive)
+    }
+
+    /// The defensive properties, Defense and Magic Defense.
+    pub fn defensive(&self) -> impl Iterator<Item = (&str, &Attribute)> {
+        self.in_category(AttributeCategory::Defensive)
+    }
+
+    /// The physical properties, Attack Power and Skill Speed.
+    pub fn physical(&self) -> impl Iterator<Item = (&str, &Attribute)> {
+        self.in_category(AttributeCategory::Physical)
+    }
+
+    /// The mental properties, such as Spell Speed.
+    pub fn mental(&self) -> impl Iterator<Item = (&str, &Attribute)> {
+        self.in_category(AttributeCategory::Mental)
+    }
+
+    /// The role specific properties, Tenacity and Piety.
+    pub fn role(&self) -> impl Iterator<Item = (&str, &Attribute)> {
+        self.in_category(AttributeCategory::Role)
+    }
+
+    /// The crafting properties, shown when a crafter is the active class.
+    pub fn crafting(&self) -> impl Iterator<Item = (&str, &Attribute)> {
+        self.in_category(AttributeCategory::Crafting)
+    }
+
+    /// The gathering properties, shown when a gatherer is the active class.
+    pub fn gathering(&self) -> impl Iterator<Item = (&str, &Attribute)> {
+        self.in_category(AttributeCategory::Gathering)
     }
 }
 
+impl IntoIterator for Attributes {
+    type Item = (String, Attribute);
+    type IntoIter = std::vec::IntoIter<(String, Attribute)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Attributes {
+    type Item = &'a (String, Attribute);
+    type IntoIter = std::slice::Iter<'a, (String, Attribute)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.iter()
+    }
+}
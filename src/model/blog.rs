@@ -0,0 +1,201 @@
+use failure::Error;
+use select::document::Document;
+use select::node::Node;
+use select::predicate::{Class, Name};
+
+use crate::diagnostics::parse_or_dump;
+use crate::model::util::{BASE_PROFILE_URL, ensure_node, fetch_html, parse_strftime_timestamp, text_with_breaks};
+use crate::pagination::{Page, Paginated, Pager, parse_page_numbers};
+
+/// A blog entry as shown in a character's list of entries.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct BlogEntrySummary {
+    /// The lodestone's id for the entry.
+    pub id: u64,
+    /// The entry's title.
+    pub title: String,
+    /// When the entry was posted, as a Unix timestamp.
+    pub posted_at: Option<u64>,
+    /// The url of the entry's thumbnail, if it has one.
+    pub thumbnail: Option<String>,
+}
+
+/// A single blog entry, with its full text.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct BlogEntry {
+    /// The lodestone's id for the entry.
+    pub id: u64,
+    /// The entry's title.
+    pub title: String,
+    /// When the entry was posted, as a Unix timestamp.
+    pub posted_at: Option<u64>,
+    /// The entry's text, with line breaks as `\n`.
+    pub body: String,
+    /// The urls of the images in the entry, in the order they appear.
+    pub images: Vec<String>,
+    /// The tags the author gave the entry.
+    pub tags: Vec<String>,
+    /// How many players have liked the entry.
+    pub likes: u32,
+    /// How many comments have been left on the entry.
+    pub comments: u32,
+}
+
+impl BlogEntry {
+    /// Gets a blog entry given the lodestone ids of its author and the entry.
+    pub fn get(user_id: u32, entry_id: u64) -> Result<Self, Error> {
+        let html = fetch_html(&format!("{}{}/blog/{}/", BASE_PROFILE_URL, user_id, entry_id))?;
+        parse_or_dump(&format!("blog-{}-{}", user_id, entry_id), &html, |doc| Self::parse(entry_id, doc))
+    }
+
+    pub(crate) fn parse(id: u64, doc: &Document) -> Result<Self, Error> {
+        let title = ensure_node!(doc, Class("entry__blog__title")).text().trim().to_string();
+        let body = ensure_node!(doc, Class("entry__blog__body"));
+
+        let images = body.find(Name("img"))
+            .filter_map(|img| img.attr("src"))
+            .map(|src| src.to_string())
+            .collect();
+
+        let tags = doc.find(Class("entry__blog__tag"))
+            .flat_map(|block| block.find(Name("a")))
+            .map(|tag| tag.text().trim().to_string())
+            .filter(|tag| !tag.is_empty())
+            .collect();
+
+        let count = |class| doc.find(Class(class))
+            .next()
+            .and_then(|node| node.text().trim().replace(",", "").parse::<u32>().ok())
+            .unwrap_or(0);
+
+        Ok(BlogEntry {
+            id,
+            title,
+            posted_at: parse_time(doc.find(Class("entry__blog__time")).next()),
+            body: text_with_breaks(&body).trim().to_string(),
+            images,
+            tags,
+            likes: count("entry__blog__like__count"),
+            comments: count("entry__blog__comment__count"),
+        })
+    }
+}
+
+/// The public blog entries of a character, newest first.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Blog(Vec<BlogEntrySummary>);
+
+impl Blog {
+    /// Gets every public blog entry for a user given their lodestone user id,
+    /// walking through every page of their blog.
+    pub fn get(user_id: u32) -> Result<Self, Error> {
+        let mut entries = Vec::new();
+
+        for page in Self::pages(user_id) {
+            entries.extend(page?);
+        }
+
+        Ok(Blog(entries))
+    }
+
+    /// Creates a `Pager` over a user's blog, which fetches one page of
+    /// entries at a time as they are requested.
+    pub fn pages(user_id: u32) -> Pager<BlogPages> {
+        Pager::new(BlogPages { user_id })
+    }
+
+    /// Iterates over the entries.
+    pub fn iter(&self) -> impl Iterator<Item = &BlogEntrySummary> {
+        self.0.iter()
+    }
+
+    /// The number of entries.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether there are no public entries.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl IntoIterator for Blog {
+    type Item = BlogEntrySummary;
+    type IntoIter = std::vec::IntoIter<BlogEntrySummary>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+/// The pages of a character's blog.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BlogPages {
+    user_id: u32,
+}
+
+impl Paginated for BlogPages {
+    type Item = BlogEntrySummary;
+
+    fn fetch_page(&self, page: u32) -> Result<Page<BlogEntrySummary>, Error> {
+        let url = format!("{}{}/blog/?page={}", BASE_PROFILE_URL, self.user_id, page);
+        let html = fetch_html(&url)?;
+
+        parse_or_dump(&format!("blog-{}-page-{}", self.user_id, page), &html, |doc| {
+            let (current_page, total_pages) = parse_page_numbers(doc);
+
+            Ok(Page {
+                items: parse_blog_list(doc)?,
+                current_page,
+                total_pages,
+            })
+        })
+    }
+}
+
+/// Parses the entries of a blog list page.
+pub(crate) fn parse_blog_list(doc: &Document) -> Result<Vec<BlogEntrySummary>, Error> {
+    let mut entries = Vec::new();
+
+    for entry in doc.find(Class("entry__blog")) {
+        let link = ensure_node!(entry, Name("a"));
+        let id = parse_entry_id(link.attr("href").unwrap_or_default())?;
+
+        let title = ensure_node!(entry, Class("entry__blog__title")).text().trim().to_string();
+
+        let posted_at = parse_time(entry.find(Class("entry__blog__time")).next());
+
+        let thumbnail = entry.find(Class("entry__blog__img"))
+            .next()
+            .and_then(|block| block.find(Name("img")).next())
+            .and_then(|img| img.attr("src"))
+            .map(|src| src.to_string());
+
+        entries.push(BlogEntrySummary {
+            id,
+            title,
+            posted_at,
+            thumbnail,
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Reads the id out of a link such as `/lodestone/character/1/blog/4567890/`.
+fn parse_entry_id(href: &str) -> Result<u64, Error> {
+    Ok(href
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or_default()
+        .parse::<u64>()?)
+}
+
+/// Reads the timestamp out of the script inside a time node.
+fn parse_time(time: Option<Node>) -> Option<u64> {
+    time?.find(Name("script"))
+        .next()
+        .and_then(|script| parse_strftime_timestamp(&script.text()))
+}
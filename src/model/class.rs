@@ -1,7 +1,19 @@
-use failure::Fail;
+use failure::{Error, Fail, ensure};
+use select::document::Document;
+use select::predicate::{Class, Name};
 use std::collections::HashMap;
 use std::str::FromStr;
 
+use crate::model::{
+    profile::SearchError,
+    util::{ensure_node, load_html},
+};
+
+/// The highest level a regular class or job can reach.
+pub const LEVEL_CAP: u32 = 100;
+/// The highest level a limited job, such as Blue Mage, can reach.
+pub const LIMITED_JOB_LEVEL_CAP: u32 = 80;
+
 #[derive(Clone, Debug, Fail)]
 #[fail(display = "Invalid class type '{}'", _0)]
 pub struct ClassTypeParseError(String);
@@ -14,6 +26,52 @@ pub struct ClassInfo {
     pub max_xp: Option<u64>,
 }
 
+impl ClassInfo {
+    /// The amount of XP still needed to reach the next level.
+    /// Returns None once the class is at the level cap.
+    pub fn exp_to_next_level(&self) -> Option<u64> {
+        match (self.current_xp, self.max_xp) {
+            (Some(current), Some(max)) => Some(max.saturating_sub(current)),
+            _ => None,
+        }
+    }
+
+    /// The same as `exp_to_next_level`, named to match `xp_progress`.
+    pub fn xp_remaining(&self) -> Option<u64> {
+        self.exp_to_next_level()
+    }
+
+    /// How far through the current level this class is, from `0.0` to `1.0`.
+    /// Returns None once the class is at the level cap.
+    pub fn xp_progress(&self) -> Option<f32> {
+        match (self.current_xp, self.max_xp) {
+            (Some(current), Some(max)) if max > 0 => Some((current as f64 / max as f64).min(1.0) as f32),
+            _ => None,
+        }
+    }
+
+    /// Whether this class has reached a given level cap, such as
+    /// `ClassType::level_cap` or `LEVEL_CAP`.
+    pub fn is_max_level(&self, cap: u32) -> bool {
+        self.level >= cap
+    }
+
+    /// A short, human readable version of `exp_to_next_level`,
+    /// for example `1.2M to next level`.
+    pub fn exp_to_next_level_display(&self) -> Option<String> {
+        let exp = self.exp_to_next_level()?;
+
+        //  Anything that would round up to "1000.0K" is shown in millions instead.
+        let amount = match exp {
+            0..=999 => exp.to_string(),
+            1_000..=999_949 => format!("{:.1}K", exp as f64 / 1_000.0),
+            _ => format!("{:.1}M", exp as f64 / 1_000_000.0),
+        };
+
+        Some(format!("{} to next level", amount))
+    }
+}
+
 /// An enum over the types of classes or jobs that are available.
 /// 
 /// In the case of unlocking a job, the higher level one is preferred.
@@ -66,6 +124,111 @@ pub enum ClassType {
     Fisher,
 }
 
+/// The broad role a class or job fills.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Role {
+    Tank,
+    Healer,
+    Damage,
+    Crafter,
+    Gatherer,
+}
+
+impl ClassType {
+    /// The role this class or job fills.
+    pub fn role(self) -> Role {
+        match self {
+            ClassType::Paladin | ClassType::Gladiator | ClassType::Warrior | ClassType::Marauder
+            | ClassType::DarkKnight | ClassType::Gunbreaker => Role::Tank,
+            ClassType::WhiteMage | ClassType::Conjurer | ClassType::Scholar | ClassType::Astrologian => Role::Healer,
+            ClassType::Carpenter | ClassType::Blacksmith | ClassType::Armorer | ClassType::Goldsmith
+            | ClassType::Leatherworker | ClassType::Weaver | ClassType::Alchemist | ClassType::Culinarian => Role::Crafter,
+            ClassType::Miner | ClassType::Botanist | ClassType::Fisher => Role::Gatherer,
+            _ => Role::Damage,
+        }
+    }
+
+    /// Whether this is a limited job, such as Blue Mage.
+    ///
+    /// Limited jobs cannot join most duties, are listed separately in
+    /// game, and have a lower level cap than other jobs.
+    pub fn is_limited_job(self) -> bool {
+        matches!(self, ClassType::BlueMage)
+    }
+
+    /// The highest level this class or job can reach.
+    pub fn level_cap(self) -> u32 {
+        match self.is_limited_job() {
+            true => LIMITED_JOB_LEVEL_CAP,
+            false => LEVEL_CAP,
+        }
+    }
+
+    /// The base class a job is unlocked from, e.g. Gladiator for Paladin.
+    ///
+    /// Returns None for base classes themselves and for jobs that do not
+    /// start from a class, such as Dark Knight or Blue Mage.
+    pub fn base_class(self) -> Option<ClassType> {
+        match self {
+            ClassType::Paladin => Some(ClassType::Gladiator),
+            ClassType::Warrior => Some(ClassType::Marauder),
+            ClassType::WhiteMage => Some(ClassType::Conjurer),
+            ClassType::Scholar => Some(ClassType::Arcanist),
+            ClassType::Monk => Some(ClassType::Pugilist),
+            ClassType::Dragoon => Some(ClassType::Lancer),
+            ClassType::Ninja => Some(ClassType::Rogue),
+            ClassType::Bard => Some(ClassType::Archer),
+            ClassType::BlackMage => Some(ClassType::Thaumaturge),
+            ClassType::Summoner => Some(ClassType::Arcanist),
+            _ => None,
+        }
+    }
+
+    /// The three letter abbreviation shown on gear, e.g. `PLD` for Paladin.
+    pub fn abbreviation(self) -> &'static str {
+        match self {
+            ClassType::Paladin => "PLD",
+            ClassType::Gladiator => "GLD",
+            ClassType::Warrior => "WAR",
+            ClassType::Marauder => "MRD",
+            ClassType::DarkKnight => "DRK",
+            ClassType::Gunbreaker => "GNB",
+            ClassType::WhiteMage => "WHM",
+            ClassType::Conjurer => "CNJ",
+            ClassType::Scholar => "SCH",
+            ClassType::Astrologian => "AST",
+            ClassType::Monk => "MNK",
+            ClassType::Pugilist => "PUG",
+            ClassType::Dragoon => "DRG",
+            ClassType::Lancer => "LNC",
+            ClassType::Ninja => "NIN",
+            ClassType::Rogue => "ROG",
+            ClassType::Samurai => "SAM",
+            ClassType::Bard => "BRD",
+            ClassType::Archer => "ARC",
+            ClassType::Machinist => "MCH",
+            ClassType::Dancer => "DNC",
+            ClassType::BlackMage => "BLM",
+            ClassType::Thaumaturge => "THM",
+            ClassType::Summoner => "SMN",
+            ClassType::Arcanist => "ACN",
+            ClassType::RedMage => "RDM",
+            ClassType::BlueMage => "BLU",
+            ClassType::Carpenter => "CRP",
+            ClassType::Blacksmith => "BSM",
+            ClassType::Armorer => "ARM",
+            ClassType::Goldsmith => "GSM",
+            ClassType::Leatherworker => "LTW",
+            ClassType::Weaver => "WVR",
+            ClassType::Alchemist => "ALC",
+            ClassType::Culinarian => "CUL",
+            ClassType::Miner => "MIN",
+            ClassType::Botanist => "BTN",
+            ClassType::Fisher => "FSH",
+        }
+    }
+}
+
 /// Takes a string from lodestone and converts it to a ClassType.
 /// Can take either the full name, or its common abbreviation as
 /// shown on gear and the conversion is case insensitive.
@@ -122,19 +285,153 @@ impl FromStr for ClassType {
 
 /// Holds information about a profile's level/XP in a particular class.
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
-pub struct Classes(HashMap<ClassType, Option<ClassInfo>>);
+pub struct Classes {
+    levels: HashMap<ClassType, Option<ClassInfo>>,
+    icons: HashMap<ClassType, String>,
+}
 
 impl Classes {
     pub fn new() -> Self {
-        Classes(HashMap::new())
+        Classes {
+            levels: HashMap::new(),
+            icons: HashMap::new(),
+        }
     }
     /// Adds or updates a given entry.
     pub fn insert(&mut self, kind: ClassType, class: Option<ClassInfo>) {
-        self.0.insert(kind, class);
+        self.levels.insert(kind, class);
     }
 
     /// Gets a class by name, if found
     pub fn get(&self, class: ClassType) -> Option<ClassInfo> {
-        *self.0.get(&class).unwrap_or(&None)
+        *self.levels.get(&class).unwrap_or(&None)
+    }
+
+    /// The url of the icon the lodestone shows for a class or job, if found.
+    ///
+    /// Icons are listed for locked classes too, so this is kept apart
+    /// from `ClassInfo`.
+    pub fn icon(&self, class: ClassType) -> Option<&str> {
+        self.icons.get(&class).map(String::as_str)
+    }
+
+    /// The class or job a given icon url belongs to, if found.
+    pub fn class_for_icon(&self, url: &str) -> Option<ClassType> {
+        self.icons.iter().find(|(_, icon)| *icon == url).map(|(kind, _)| *kind)
+    }
+
+    /// Sets the url of the icon for a class or job.
+    pub fn insert_icon(&mut self, kind: ClassType, url: String) {
+        self.icons.insert(kind, url);
+    }
+
+    /// Iterates over the limited jobs that have been unlocked.
+    pub fn limited_jobs(&self) -> impl Iterator<Item = (ClassType, ClassInfo)> + '_ {
+        self.iter().filter(|(kind, _)| kind.is_limited_job())
+    }
+
+    /// Iterates over the classes that have been unlocked, in no particular order.
+    ///
+    /// Once a job is unlocked its base class is listed too, with the same level.
+    pub fn iter(&self) -> impl Iterator<Item = (ClassType, ClassInfo)> + '_ {
+        self.levels.iter().filter_map(|(kind, info)| info.map(|info| (*kind, info)))
+    }
+
+    /// Iterates over the unlocked classes, leaving out base classes whose
+    /// job has been unlocked, so that every level is only listed once.
+    ///
+    /// Jobs that share a base class, such as Scholar and Summoner, also
+    /// share a level, so only the one listed first in `ClassType` is kept.
+    pub fn jobs(&self) -> impl Iterator<Item = (ClassType, ClassInfo)> + '_ {
+        self.iter()
+            .filter(move |(kind, _)| !self.iter().any(|(job, _)| job.base_class() == Some(*kind)))
+            .filter(move |(kind, _)| match kind.base_class() {
+                Some(base) => !self.iter().any(|(job, _)| job < *kind && job.base_class() == Some(base)),
+                None => true,
+            })
+    }
+
+    /// The unlocked job with the highest level. Ties go to the job listed
+    /// first in `ClassType`, so tanks before healers before damage.
+    pub fn highest_level_job(&self) -> Option<(ClassType, ClassInfo)> {
+        self.sorted_by_level().into_iter().next()
+    }
+
+    /// The sum of the levels of every unlocked job, counting a job and its
+    /// base class, or jobs sharing a base class, once.
+    pub fn total_levels(&self) -> u32 {
+        self.jobs().map(|(_, info)| info.level).sum()
+    }
+
+    /// The unlocked jobs, highest level first, as listed by `jobs`.
+    pub fn sorted_by_level(&self) -> Vec<(ClassType, ClassInfo)> {
+        let mut jobs = self.jobs().collect::<Vec<_>>();
+        jobs.sort_by(|(a_kind, a), (b_kind, b)| b.level.cmp(&a.level).then(a_kind.cmp(b_kind)));
+        jobs
+    }
+
+    /// Fetches only the classes of a character, without its profile.
+    ///
+    /// This is not a `LodestoneResource`, since the classes are a page of
+    /// a character rather than something the lodestone identifies on its own.
+    pub fn fetch(user_id: u32) -> Result<Self, Error> {
+        let html = load_html(user_id, Some("class_job"))?;
+        Self::parse(&Document::from(html.as_str()))
+    }
+
+    /// Parses the classes from a character's class_job page.
+    pub(crate) fn parse(doc: &Document) -> Result<Self, Error> {
+        let mut classes = Classes::new();
+
+        for list in doc.find(Class("character__content")).take(4) {
+            for item in list.find(Name("li")) {
+                let name = ensure_node!(item, Class("character__job__name")).text();
+                let classinfo = match ensure_node!(item, Class("character__job__level")).text().as_str() {
+                    "-" => None,
+                    level => {
+                        let text = ensure_node!(item, Class("character__job__exp")).text();
+                        let mut parts = text.split(" / ");
+                        let current_xp = parts.next();
+                        ensure!(current_xp.is_some(), SearchError::InvalidData("character__job__exp".into()));
+                        let max_xp = parts.next();
+                        ensure!(max_xp.is_some(), SearchError::InvalidData("character__job__exp".into()));
+                        Some(ClassInfo{
+                            level: level.parse()?,
+                            current_xp: match current_xp.unwrap() {
+                                "--" => None,
+                                value => Some(value.replace(",", "").parse()?)
+                            },
+                            max_xp: match max_xp.unwrap() {
+                                "--" => None,
+                                value => Some(value.replace(",", "").parse()?)
+                            },
+                        })
+                    }
+                };
+
+                //  For classes that have multiple titles (e.g., Paladin / Gladiator), grab the first one.
+                let name = name.split(" / ").next();
+                ensure!(name.is_some(), SearchError::InvalidData("character__job__name".into()));
+                let class = ClassType::from_str(name.unwrap())?;
+
+                //  If the class added was a secondary job, then associated that level
+                //  with its lower level counterpart as well. This makes returning the
+                //  level for a particular grouping easier at the cost of memory.
+                //  Scholar and Summoner share Arcanist, so the first one listed wins.
+                if let (Some(base), Some(_)) = (class.base_class(), classinfo) {
+                    if classes.get(base).is_none() {
+                        classes.insert(base, classinfo);
+                    }
+                }
+
+                if let Some(icon) = item.find(Name("img")).next().and_then(|img| img.attr("src")) {
+                    classes.insert_icon(class, icon.to_string());
+                }
+
+                classes.insert(class, classinfo);
+            }
+        }
+
+        Ok(classes)
     }
 }
@@ -0,0 +1,33 @@
+use failure::{Error, ensure};
+use image::{RgbaImage, imageops};
+
+use crate::model::profile::SearchError;
+use crate::model::util::fetch_bytes;
+
+/// Downloads the layers of a crest and draws them on top of each other,
+/// from the first layer to the last, as the lodestone does.
+///
+/// The result is the size of the first layer; the lodestone serves every
+/// layer at the same size, so nothing is scaled.
+pub fn composite(layers: &[String]) -> Result<RgbaImage, Error> {
+    ensure!(!layers.is_empty(), SearchError::InvalidData("crest has no layers".into()));
+
+    let mut crest: Option<RgbaImage> = None;
+    for url in layers {
+        let layer = image::load_from_memory(&fetch_bytes(url)?)?.to_rgba8();
+        match crest.as_mut() {
+            Some(crest) => imageops::overlay(crest, &layer, 0, 0),
+            None => crest = Some(layer),
+        }
+    }
+
+    Ok(crest.unwrap())
+}
+
+/// Composites the layers of a crest and encodes the result as a PNG.
+pub fn composite_png(layers: &[String]) -> Result<Vec<u8>, Error> {
+    let crest = composite(layers)?;
+    let mut png = Vec::new();
+    crest.write_to(&mut std::io::Cursor::new(&mut png), image::ImageOutputFormat::Png)?;
+    Ok(png)
+}
@@ -19,20 +19,37 @@ pub enum Datacenter {
 }
 
 /// Case insensitive FromStr impl for datacenters.
+///
+/// Surrounding whitespace, the bracketed form shown next to world names
+/// (e.g. `[Primal]`), and a trailing `DC` or `Data Center` are ignored,
+/// so `primal`, `[Primal]` and `Primal DC` all convert to
+/// `Datacenter::Primal`. The Japanese names of the Japanese datacenters
+/// are accepted as well.
 impl FromStr for Datacenter {
     type Err = DatacenterParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match &*s.to_uppercase() {
+        let upper = s.trim()
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .trim()
+            .to_uppercase();
+
+        let name = ["DATA CENTER", "DATACENTER", "DC"].iter()
+            .find_map(|suffix| upper.strip_suffix(suffix))
+            .unwrap_or(&upper)
+            .trim();
+
+        match name {
             "AETHER" => Ok(Datacenter::Aether),
             "CHAOS" => Ok(Datacenter::Chaos),
             "CRYSTAL" => Ok(Datacenter::Crystal),
-            "ELEMENTAL" => Ok(Datacenter::Elemental),
-            "GAIA" => Ok(Datacenter::Gaia),
+            "ELEMENTAL" | "エレメンタル" => Ok(Datacenter::Elemental),
+            "GAIA" | "ガイア" => Ok(Datacenter::Gaia),
             "LIGHT" => Ok(Datacenter::Light),
-            "MANA" => Ok(Datacenter::Mana),
+            "MANA" | "マナ" => Ok(Datacenter::Mana),
             "PRIMAL" => Ok(Datacenter::Primal),
-            x => Err(DatacenterParseError(x.into())),
+            _ => Err(DatacenterParseError(s.into())),
         }
     }
 }
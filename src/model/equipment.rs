@@ -0,0 +1,218 @@
+use crate::model::class::ClassType;
+
+/// The gear slots shown on a character's profile.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum GearSlot {
+    MainHand,
+    OffHand,
+    Head,
+    Body,
+    Hands,
+    Legs,
+    Feet,
+    Earrings,
+    Necklace,
+    Bracelets,
+    Ring1,
+    Ring2,
+    SoulCrystal,
+}
+
+impl GearSlot {
+    /// Every slot, in the order the lodestone lays them out.
+    pub const ALL: [GearSlot; 13] = [
+        GearSlot::MainHand,
+        GearSlot::OffHand,
+        GearSlot::Head,
+        GearSlot::Body,
+        GearSlot::Hands,
+        GearSlot::Legs,
+        GearSlot::Feet,
+        GearSlot::Earrings,
+        GearSlot::Necklace,
+        GearSlot::Bracelets,
+        GearSlot::Ring1,
+        GearSlot::Ring2,
+        GearSlot::SoulCrystal,
+    ];
+
+    /// The number the lodestone uses for this slot in its `icon-c--N` classes.
+    /// Number 5 was the waist slot, which no longer exists.
+    pub(crate) fn icon_index(self) -> u8 {
+        match self {
+            GearSlot::MainHand => 0,
+            GearSlot::OffHand => 1,
+            GearSlot::Head => 2,
+            GearSlot::Body => 3,
+            GearSlot::Hands => 4,
+            GearSlot::Legs => 6,
+            GearSlot::Feet => 7,
+            GearSlot::Earrings => 8,
+            GearSlot::Necklace => 9,
+            GearSlot::Bracelets => 10,
+            GearSlot::Ring1 => 11,
+            GearSlot::Ring2 => 12,
+            GearSlot::SoulCrystal => 13,
+        }
+    }
+}
+
+/// A materia melded into a piece of gear.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Materia {
+    /// The materia's full name, e.g. "Savage Aim Materia X".
+    pub name: String,
+    /// The materia's grade, read from the numeral at the end of its name.
+    pub tier: Option<u8>,
+    /// The bonus it grants as shown on the tooltip, e.g. "Critical Hit +36".
+    pub bonus: Option<String>,
+}
+
+impl Materia {
+    /// Reads the grade from the roman numeral that ends a materia's name.
+    pub(crate) fn parse_tier(name: &str) -> Option<u8> {
+        let numeral = name.rsplit(' ').next()?;
+
+        let mut total = 0u8;
+        let mut previous = 0u8;
+        for ch in numeral.chars().rev() {
+            let value = match ch {
+                'I' => 1,
+                'V' => 5,
+                'X' => 10,
+                _ => return None,
+            };
+
+            if value < previous {
+                total = total.checked_sub(value)?;
+            } else {
+                total = total.checked_add(value)?;
+                previous = value;
+            }
+        }
+
+        Some(total).filter(|tier| *tier > 0)
+    }
+}
+
+/// How a piece of gear has been changed in appearance.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Glamour {
+    /// The item whose appearance is projected onto the gear, if any.
+    pub item: Option<String>,
+    /// The name of the dye applied, if any.
+    pub dye: Option<String>,
+}
+
+/// An item equipped in one of a character's gear slots.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EquippedItem {
+    /// Which slot the item is equipped in.
+    pub slot: GearSlot,
+    /// The item's name.
+    pub name: String,
+    /// The item's category as shown on its tooltip, e.g. "Two-handed Thaumaturge's Arm".
+    pub category: String,
+    /// The item's level, if the tooltip shows one.
+    pub item_level: Option<u32>,
+    /// The materia melded into the item, in socket order.
+    pub materia: Vec<Materia>,
+    /// The glamour and dye on the item, if it has either.
+    pub glamour: Option<Glamour>,
+}
+
+/// Holds the items a character currently has equipped.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Equipment(Vec<EquippedItem>);
+
+impl Equipment {
+    pub fn new() -> Self {
+        Equipment(Vec::with_capacity(GearSlot::ALL.len()))
+    }
+
+    /// Adds or replaces the item in a slot.
+    pub fn insert(&mut self, item: EquippedItem) {
+        self.0.retain(|existing| existing.slot != item.slot);
+        self.0.push(item);
+        self.0.sort_by_key(|item| item.slot);
+    }
+
+    /// Borrows the item in a given slot, if one is equipped.
+    pub fn get(&self, slot: GearSlot) -> Option<&EquippedItem> {
+        self.0.iter().find(|item| item.slot == slot)
+    }
+
+    /// The class or job these items are equipped on.
+    ///
+    /// This is read from the soul crystal, e.g. "Soul of the Paladin",
+    /// and otherwise from the main hand's category, e.g. "Gladiator's Arm"
+    /// or "Two-handed Thaumaturge's Arm".
+    pub fn active_class(&self) -> Option<ClassType> {
+        let from_crystal = self.get(GearSlot::SoulCrystal)
+            .and_then(|item| item.name.strip_prefix("Soul of the "))
+            .and_then(|job| job.parse().ok());
+
+        from_crystal.or_else(|| {
+            let category = &self.get(GearSlot::MainHand)?.category;
+            let category = ["One-handed ", "Two-handed "].iter()
+                .find_map(|prefix| category.strip_prefix(prefix))
+                .unwrap_or(category);
+            category.split("'s ").next()?.parse().ok()
+        })
+    }
+
+    /// Iterates over the equipped items in slot order.
+    pub fn iter(&self) -> impl Iterator<Item = &EquippedItem> {
+        self.0.iter()
+    }
+
+    /// The number of slots that have an item equipped.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether nothing is equipped at all.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The average item level of the equipped gear, calculated the same
+    /// way the game does it.
+    ///
+    /// The soul crystal is ignored and every other slot counts, with empty
+    /// slots counting as zero. Most weapons fill the off hand as well, so
+    /// when the off hand is empty the main hand's item level is counted
+    /// twice. The exceptions are weapons that are used with an off-hand
+    /// item: Gladiator's Arms, one-handed Thaumaturge's and Conjurer's Arms,
+    /// and the primary tools of crafters and gatherers.
+    pub fn average_item_level(&self) -> Option<u32> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let level = |slot| self.get(slot).and_then(|item| item.item_level).unwrap_or(0);
+
+        let slots = GearSlot::ALL.iter()
+            .filter(|slot| **slot != GearSlot::SoulCrystal)
+            .collect::<Vec<_>>();
+
+        let mut total = slots.iter().map(|slot| level(**slot)).sum::<u32>();
+
+        let fills_off_hand = self.get(GearSlot::MainHand)
+            .map(|weapon| !takes_off_hand(&weapon.category))
+            .unwrap_or(false);
+        if fills_off_hand && self.get(GearSlot::OffHand).is_none() {
+            total += level(GearSlot::MainHand);
+        }
+
+        Some(total / slots.len() as u32)
+    }
+}
+
+/// Whether a main hand of the given category is used with an off-hand
+/// item, such as a shield or a secondary tool.
+fn takes_off_hand(category: &str) -> bool {
+    category == "Gladiator's Arm"
+        || category.starts_with("One-handed ")
+        || category.ends_with("'s Primary Tool")
+}
@@ -0,0 +1,588 @@
+use failure::{Error, Fail, ensure};
+use select::document::Document;
+use select::node::Node;
+use select::predicate::{Class, Name};
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::diagnostics::{dump_parse_failure, parse_or_dump};
+use crate::model::{
+    class::Role,
+    datacenter::Datacenter,
+    gc::GrandCompany,
+    id::LodestoneId,
+    member::MemberEntry,
+    profile::SearchError,
+    resource::LodestoneResource,
+    server::Server,
+    util::{ensure_node, fetch_html, parse_strftime_timestamp, text_with_breaks},
+};
+use crate::pagination::{Items, Page, Paginated, Pager, parse_page_numbers};
+
+#[derive(Clone, Debug, Fail)]
+#[fail(display = "Invalid reputation string '{}'", _0)]
+pub struct ReputationParseError(String);
+
+/// A free company's standing with a grand company, from lowest to highest.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Reputation {
+    Neutral,
+    Recognized,
+    Friendly,
+    Trusted,
+    Respected,
+    Honored,
+    Sworn,
+    Allied,
+}
+
+/// Case insensitive FromStr impl for reputation levels.
+impl FromStr for Reputation {
+    type Err = ReputationParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match &*s.trim().to_uppercase() {
+            "NEUTRAL" => Ok(Reputation::Neutral),
+            "RECOGNIZED" => Ok(Reputation::Recognized),
+            "FRIENDLY" => Ok(Reputation::Friendly),
+            "TRUSTED" => Ok(Reputation::Trusted),
+            "RESPECTED" => Ok(Reputation::Respected),
+            "HONORED" => Ok(Reputation::Honored),
+            "SWORN" => Ok(Reputation::Sworn),
+            "ALLIED" => Ok(Reputation::Allied),
+            x => Err(ReputationParseError(x.into())),
+        }
+    }
+}
+
+impl fmt::Display for Reputation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let reputation = match *self {
+            Reputation::Neutral => "Neutral",
+            Reputation::Recognized => "Recognized",
+            Reputation::Friendly => "Friendly",
+            Reputation::Trusted => "Trusted",
+            Reputation::Respected => "Respected",
+            Reputation::Honored => "Honored",
+            Reputation::Sworn => "Sworn",
+            Reputation::Allied => "Allied",
+        };
+        write!(f, "{}", reputation)
+    }
+}
+
+#[derive(Clone, Debug, Fail)]
+#[fail(display = "Invalid focus string '{}'", _0)]
+pub struct FocusParseError(String);
+
+/// The activities a free company says it focuses on.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Focus {
+    RolePlaying,
+    Leveling,
+    Casual,
+    Hardcore,
+    Dungeons,
+    Guildhests,
+    Trials,
+    Raids,
+    PvP,
+}
+
+/// Case insensitive FromStr impl for the focus labels on a free company page.
+impl FromStr for Focus {
+    type Err = FocusParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match &*s.trim().to_uppercase() {
+            "ROLE-PLAYING" | "ROLEPLAYING" => Ok(Focus::RolePlaying),
+            "LEVELING" => Ok(Focus::Leveling),
+            "CASUAL" => Ok(Focus::Casual),
+            "HARDCORE" => Ok(Focus::Hardcore),
+            "DUNGEONS" => Ok(Focus::Dungeons),
+            "GUILDHESTS" => Ok(Focus::Guildhests),
+            "TRIALS" => Ok(Focus::Trials),
+            "RAIDS" => Ok(Focus::Raids),
+            "PVP" => Ok(Focus::PvP),
+            x => Err(FocusParseError(x.into())),
+        }
+    }
+}
+
+/// When a free company says its members are active.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum ActiveTime {
+    Always,
+    Weekdays,
+    Weekends,
+}
+
+/// A reference to a free company, as shown on a member's profile.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct FreeCompanyRef {
+    /// The lodestone's id for the free company.
+    pub id: u64,
+    /// The free company's name.
+    pub name: String,
+    /// The urls of the images that are layered to make the company crest,
+    /// from the bottom layer to the top.
+    pub crest: Vec<String>,
+}
+
+impl FreeCompanyRef {
+    /// The url of the free company's page on the lodestone.
+    pub fn lodestone_url(&self) -> String {
+        LodestoneId::FreeCompany(self.id).lodestone_url()
+    }
+
+    /// Downloads the crest layers and composites them into one image.
+    #[cfg(feature = "image")]
+    pub fn crest_image(&self) -> Result<image::RgbaImage, Error> {
+        crate::model::crest::composite(&self.crest)
+    }
+
+    /// Fetches the full free company this refers to.
+    pub fn fetch(&self) -> Result<FreeCompany, Error> {
+        FreeCompany::get(self.id)
+    }
+
+    /// Checks whether the free company this refers to has since been
+    /// renamed or disbanded. See `FreeCompany::check`.
+    pub fn check(&self) -> Result<FreeCompanyStatus, Error> {
+        FreeCompany::check(self.id, &self.name)
+    }
+}
+
+/// Holds the data for a free company retrieved via Lodestone.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FreeCompany {
+    /// The lodestone's id for the free company.
+    pub id: u64,
+    /// The free company's name.
+    pub name: String,
+    /// The company tag shown after members' names, without the
+    /// surrounding `«»`.
+    pub tag: String,
+    /// The urls of the images that are layered to make the company crest,
+    /// from the bottom layer to the top.
+    pub crest: Vec<String>,
+    /// The company slogan, if one has been set, with line breaks as `\n`.
+    pub slogan: Option<String>,
+    /// The grand company the free company is allied with.
+    pub grand_company: GrandCompany,
+    /// Which server the free company is on.
+    pub server: Server,
+    /// Which datacenter the free company's server belongs to.
+    pub datacenter: Datacenter,
+    /// The company's rank, from 1 to 30.
+    pub rank: u8,
+    /// When the company was formed, as a Unix timestamp.
+    pub formed_at: Option<u64>,
+    /// How many members the company has.
+    pub active_members: u32,
+    /// Whether the company is currently recruiting.
+    pub recruiting: bool,
+    /// When the company's members are active, if it has said.
+    pub active: Option<ActiveTime>,
+    /// The activities the company focuses on.
+    pub focus: HashSet<Focus>,
+    /// The roles the company is looking to recruit.
+    pub seeking: HashSet<Role>,
+    /// The company's house, if it owns one.
+    pub estate: Option<FreeCompanyEstate>,
+    /// The company's placements on the leaderboards.
+    pub ranking: FreeCompanyRanking,
+    /// The company's standing with each grand company.
+    pub reputation: HashMap<GrandCompany, Reputation>,
+}
+
+/// A member of a free company, as listed on its member page.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct FcMember {
+    /// The id, name, and world shared with other rosters.
+    pub entry: MemberEntry,
+    /// The name of the member's rank within the company, e.g. "Officer".
+    pub rank_name: String,
+    /// The url of the rank's icon.
+    pub rank_icon: Option<String>,
+}
+
+impl AsRef<MemberEntry> for FcMember {
+    fn as_ref(&self) -> &MemberEntry {
+        &self.entry
+    }
+}
+
+/// A placement on one of the free company leaderboards.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub struct RankingPlacement {
+    /// The current placement, if the company is ranked.
+    pub current: Option<u32>,
+    /// The placement in the previous period, if the company was ranked.
+    pub previous: Option<u32>,
+}
+
+/// A free company's placements on the weekly and monthly leaderboards.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub struct FreeCompanyRanking {
+    pub weekly: RankingPlacement,
+    pub monthly: RankingPlacement,
+}
+
+/// What became of a free company since it was last seen.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum FreeCompanyStatus {
+    /// The company still exists under the expected name.
+    Active(FreeCompany),
+    /// The company still exists, but under a different name.
+    Renamed {
+        company: FreeCompany,
+        previous_name: String,
+    },
+    /// The company's page no longer exists, so it has been disbanded.
+    Disbanded,
+}
+
+/// A free company's house, as shown in the Estate Profile.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct FreeCompanyEstate {
+    /// The name given to the estate.
+    pub name: String,
+    /// Where the plot is, e.g. "Plot 5, 12 Ward, Mist (Medium)".
+    pub address: String,
+    /// The greeting shown to visitors, if one has been set.
+    pub greeting: Option<String>,
+}
+
+impl FreeCompany {
+    /// Gets a free company given its lodestone id.
+    pub fn get(id: u64) -> Result<Self, Error> {
+        let html = fetch_html(&LodestoneId::FreeCompany(id).lodestone_url())?;
+
+        Self::parse_from_html(id, &html).inspect_err(|e| {
+            dump_parse_failure(&format!("freecompany-{}", id), &[("freecompany", html.as_str())], e);
+        })
+    }
+
+    /// Gets a free company and checks it against the name it was last
+    /// known by, to tell trackers whether it was renamed or disbanded.
+    ///
+    /// A missing page means the company was disbanded. Any other failure,
+    /// such as a parse error or maintenance, is still returned as an error.
+    pub fn check(id: u64, expected_name: &str) -> Result<FreeCompanyStatus, Error> {
+        let company = match Self::get(id) {
+            Ok(company) => company,
+            Err(e) => return match e.downcast_ref::<SearchError>() {
+                Some(SearchError::NotFound) => Ok(FreeCompanyStatus::Disbanded),
+                _ => Err(e),
+            },
+        };
+
+        match company.name == expected_name {
+            true => Ok(FreeCompanyStatus::Active(company)),
+            false => Ok(FreeCompanyStatus::Renamed {
+                company,
+                previous_name: expected_name.to_string(),
+            }),
+        }
+    }
+
+    /// Parses a free company from the HTML of its page, which has already
+    /// been downloaded. Nothing is fetched.
+    pub fn parse_from_html(id: u64, html: &str) -> Result<Self, Error> {
+        Self::parse(id, &Document::from(html))
+    }
+
+    /// The url of the free company's page on the lodestone. Without a
+    /// `FreeCompany` at hand, use `LodestoneId::FreeCompany(id).lodestone_url()`.
+    pub fn lodestone_url(&self) -> String {
+        LodestoneId::FreeCompany(self.id).lodestone_url()
+    }
+
+    pub(crate) fn parse(id: u64, doc: &Document) -> Result<Self, Error> {
+        let (server, datacenter) = Self::parse_world(doc)?;
+
+        Ok(Self {
+            id,
+            name: ensure_node!(doc, Class("entry__freecompany__name")).text().trim().to_string(),
+            tag: Self::parse_tag(doc)?,
+            crest: parse_crest(doc, "entry__freecompany__crest__image"),
+            slogan: section(doc, "Company Slogan")
+                .map(|node| text_with_breaks(&node).trim().to_string())
+                .filter(|slogan| !slogan.is_empty()),
+            grand_company: Self::parse_grand_company(doc)?,
+            server,
+            datacenter,
+            rank: Self::parse_number(doc, "Rank")?,
+            formed_at: section(doc, "Formed")
+                .and_then(|node| node.find(Name("script")).next())
+                .and_then(|script| parse_strftime_timestamp(&script.text())),
+            active_members: Self::parse_number(doc, "Active Members")?,
+            recruiting: section(doc, "Recruitment")
+                .map(|node| node.text().trim().eq_ignore_ascii_case("open"))
+                .unwrap_or(false),
+            active: section(doc, "Active").and_then(|node| match &*node.text().trim().to_uppercase() {
+                "ALWAYS" => Some(ActiveTime::Always),
+                "WEEKDAYS ONLY" | "WEEKDAYS" => Some(ActiveTime::Weekdays),
+                "WEEKENDS ONLY" | "WEEKENDS" => Some(ActiveTime::Weekends),
+                _ => None,
+            }),
+            focus: Self::parse_icons(doc, "Focus")?
+                .iter()
+                .map(|name| Focus::from_str(name))
+                .collect::<Result<_, _>>()?,
+            seeking: Self::parse_icons(doc, "Seeking")?
+                .iter()
+                .map(|name| Self::parse_role(name))
+                .collect::<Result<_, _>>()?,
+            estate: Self::parse_estate(doc)?,
+            ranking: Self::parse_ranking(doc),
+            reputation: Self::parse_reputation(doc)?,
+        })
+    }
+
+    /// Reads the labels of the icons that are switched on in an icon grid,
+    /// such as Focus. Icons that are switched off have an `--off` class.
+    fn parse_icons(doc: &Document, heading: &str) -> Result<Vec<String>, Error> {
+        let grid = match section(doc, heading) {
+            Some(grid) => grid,
+            None => return Ok(Vec::new()),
+        };
+
+        Ok(grid.find(Name("li"))
+            .filter(|icon| !icon.attr("class").unwrap_or_default().contains("--off"))
+            .map(|icon| icon.text().trim().to_string())
+            .filter(|name| !name.is_empty())
+            .collect())
+    }
+
+    fn parse_role(name: &str) -> Result<Role, Error> {
+        match &*name.to_uppercase() {
+            "TANK" => Ok(Role::Tank),
+            "HEALER" => Ok(Role::Healer),
+            "DPS" => Ok(Role::Damage),
+            "CRAFTER" => Ok(Role::Crafter),
+            "GATHERER" => Ok(Role::Gatherer),
+            _ => Err(SearchError::InvalidData(format!("Seeking '{}'", name)).into()),
+        }
+    }
+
+    fn parse_ranking(doc: &Document) -> FreeCompanyRanking {
+        let mut ranking = FreeCompanyRanking::default();
+        let block = match section(doc, "Ranking") {
+            Some(block) => block,
+            None => return ranking,
+        };
+
+        //  Rows read e.g. "Weekly Rank: 12 (Previous Week: 15)", with "--"
+        //  in place of a number when the company was not ranked.
+        for row in block.find(Name("th")) {
+            let text = row.text();
+            let placement = match text.to_lowercase() {
+                lower if lower.contains("weekly") => &mut ranking.weekly,
+                lower if lower.contains("monthly") => &mut ranking.monthly,
+                _ => continue,
+            };
+
+            let number = |part: &str| part
+                .split(|ch: char| !ch.is_ascii_digit())
+                .find_map(|digits| digits.parse::<u32>().ok());
+            let mut parts = text.splitn(2, '(');
+            placement.current = parts.next().and_then(number);
+            placement.previous = parts.next().and_then(number);
+        }
+
+        ranking
+    }
+
+    fn parse_reputation(doc: &Document) -> Result<HashMap<GrandCompany, Reputation>, Error> {
+        let mut reputation = HashMap::new();
+        for block in doc.find(Class("freecompany__reputation")) {
+            let gc = ensure_node!(block, Class("freecompany__reputation__gcname")).text();
+            let rank = ensure_node!(block, Class("freecompany__reputation__rank")).text();
+            reputation.insert(GrandCompany::from_str(gc.trim())?, Reputation::from_str(&rank)?);
+        }
+        Ok(reputation)
+    }
+
+    fn parse_estate(doc: &Document) -> Result<Option<FreeCompanyEstate>, Error> {
+        //  Companies without a house show "No Estate or Plot" instead.
+        if doc.find(Class("freecompany__estate__none")).next().is_some() {
+            return Ok(None);
+        }
+        let name = match doc.find(Class("freecompany__estate__name")).next() {
+            Some(name) => name.text().trim().to_string(),
+            None => return Ok(None),
+        };
+
+        Ok(Some(FreeCompanyEstate {
+            name,
+            address: ensure_node!(doc, Class("freecompany__estate__text")).text().trim().to_string(),
+            greeting: doc.find(Class("freecompany__estate__greeting"))
+                .next()
+                .map(|node| node.text().trim().to_string())
+                .filter(|greeting| !greeting.is_empty() && greeting != "-"),
+        }))
+    }
+
+    /// Downloads the crest layers and composites them into one image.
+    #[cfg(feature = "image")]
+    pub fn crest_image(&self) -> Result<image::RgbaImage, Error> {
+        crate::model::crest::composite(&self.crest)
+    }
+
+    /// Gets every member of a free company, walking through every page
+    /// of its member list.
+    ///
+    /// Large companies have hundreds of members over many pages. Use
+    /// `members_iter` to handle members as their page arrives instead.
+    pub fn members(id: u64) -> Result<Vec<FcMember>, Error> {
+        Self::members_iter(id).collect()
+    }
+
+    /// Iterates over the members of a free company, fetching each page of
+    /// its member list only once the members before it have been read.
+    pub fn members_iter(id: u64) -> Items<FreeCompanyMemberPages> {
+        Self::member_pages(id).items()
+    }
+
+    /// Creates a `Pager` over a free company's member list, which fetches
+    /// one page of members at a time as they are requested.
+    pub fn member_pages(id: u64) -> Pager<FreeCompanyMemberPages> {
+        Pager::new(FreeCompanyMemberPages { id })
+    }
+
+    fn parse_tag(doc: &Document) -> Result<String, Error> {
+        let tag = ensure_node!(doc, Class("freecompany__text__tag")).text();
+        Ok(tag.trim().trim_start_matches('«').trim_end_matches('»').to_string())
+    }
+
+    fn parse_grand_company(doc: &Document) -> Result<GrandCompany, Error> {
+        //  Reads e.g. "Maelstrom <Neutral>", where the part in brackets is
+        //  the company's standing with its grand company.
+        let text = ensure_node!(doc, Class("entry__freecompany__gc")).text();
+        let name = text.split('<').next().unwrap_or_default();
+        Ok(GrandCompany::from_str(name.trim())?)
+    }
+
+    fn parse_world(doc: &Document) -> Result<(Server, Datacenter), Error> {
+        //  Reads e.g. "Famfrit [Primal]".
+        let text = ensure_node!(doc, Class("entry__freecompany__gc"), 1).text();
+        parse_world_text(&text)
+    }
+
+    fn parse_number<T: FromStr>(doc: &Document, heading: &str) -> Result<T, Error> {
+        let node = section(doc, heading);
+        ensure!(node.is_some(), SearchError::NodeNotFound(heading.into()));
+        node.unwrap()
+            .text()
+            .trim()
+            .replace(",", "")
+            .parse::<T>()
+            .map_err(|_| SearchError::InvalidData(heading.into()).into())
+    }
+}
+
+impl LodestoneResource for FreeCompany {
+    type Id = u64;
+
+    fn fetch(id: u64) -> Result<Self, Error> {
+        Self::get(id)
+    }
+}
+
+/// The pages of a free company's member list.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct FreeCompanyMemberPages {
+    id: u64,
+}
+
+impl Paginated for FreeCompanyMemberPages {
+    type Item = FcMember;
+
+    fn fetch_page(&self, page: u32) -> Result<Page<FcMember>, Error> {
+        let url = format!("{}member/?page={}", LodestoneId::FreeCompany(self.id).lodestone_url(), page);
+        let html = fetch_html(&url)?;
+
+        parse_or_dump(&format!("freecompany-{}-page-{}", self.id, page), &html, |doc| {
+            let (current_page, total_pages) = parse_page_numbers(doc);
+
+            Ok(Page {
+                items: parse_member_list(doc)?,
+                current_page,
+                total_pages,
+            })
+        })
+    }
+}
+
+/// Parses the entries of a free company member page.
+pub(crate) fn parse_member_list(doc: &Document) -> Result<Vec<FcMember>, Error> {
+    let mut members = Vec::new();
+
+    for node in doc.find(Class("entry__bg")) {
+        let entry = match MemberEntry::parse(&node)? {
+            Some(entry) => entry,
+            None => continue,
+        };
+
+        let rank = node.find(Class("entry__freecompany__info")).next();
+        let rank_name = rank
+            .and_then(|info| info.find(Name("span")).next())
+            .map(|span| span.text().trim().to_string())
+            .unwrap_or_default();
+        let rank_icon = rank
+            .and_then(|info| info.find(Name("img")).next())
+            .and_then(|img| img.attr("src"))
+            .map(|src| src.to_string());
+
+        members.push(FcMember {
+            entry,
+            rank_name,
+            rank_icon,
+        });
+    }
+
+    Ok(members)
+}
+
+/// Reads a world such as "Famfrit [Primal]" into its server and datacenter.
+pub(crate) fn parse_world_text(text: &str) -> Result<(Server, Datacenter), Error> {
+    let text = text.replace('\u{A0}', " ");
+    let bracket = text.find('[');
+    ensure!(bracket.is_some(), SearchError::InvalidData(format!("world '{}'", text.trim())));
+    let (server, datacenter) = text.split_at(bracket.unwrap());
+
+    Ok((Server::from_str(server.trim())?, Datacenter::from_str(datacenter)?))
+}
+
+/// Reads the urls of the crest layers inside the block with the given class.
+pub(crate) fn parse_crest(doc: &Document, class: &str) -> Vec<String> {
+    doc.find(Class(class))
+        .next()
+        .map(|images| images
+            .find(Name("img"))
+            .filter_map(|img| img.attr("src"))
+            .map(|src| src.to_string())
+            .collect())
+        .unwrap_or_default()
+}
+
+/// Finds the block that follows a section heading on a free company page,
+/// such as the text under "Company Slogan". The heading is compared
+/// case insensitively.
+pub(crate) fn section<'a>(doc: &'a Document, heading: &str) -> Option<Node<'a>> {
+    let heading = doc.find(Name("h3"))
+        .find(|node| node.text().trim().eq_ignore_ascii_case(heading))?;
+
+    let mut next = heading.next();
+    while let Some(node) = next {
+        if node.name().is_some() {
+            return Some(node);
+        }
+        next = node.next();
+    }
+    None
+}
@@ -1,4 +1,5 @@
 use failure::Fail;
+use std::fmt;
 use std::str::FromStr;
 
 #[derive(Clone, Debug, Fail)]
@@ -25,4 +26,41 @@ impl FromStr for GrandCompany {
             x => Err(GrandCompanyParseError(x.into())),
         }
     }
-}
\ No newline at end of file
+}
+
+impl GrandCompany {
+    /// The numeric id the lodestone uses for this grand company
+    /// in query strings, e.g. `gcid=1` for the Maelstrom.
+    pub fn to_gcid(self) -> u8 {
+        match self {
+            GrandCompany::Unaffiliated => 0,
+            GrandCompany::Maelstrom => 1,
+            GrandCompany::TwinAdder => 2,
+            GrandCompany::ImmortalFlames => 3,
+        }
+    }
+
+    /// Converts a lodestone grand company id back into a grand company.
+    pub fn from_gcid(gcid: u8) -> Option<Self> {
+        match gcid {
+            0 => Some(GrandCompany::Unaffiliated),
+            1 => Some(GrandCompany::Maelstrom),
+            2 => Some(GrandCompany::TwinAdder),
+            3 => Some(GrandCompany::ImmortalFlames),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for GrandCompany {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let gc = match *self {
+            GrandCompany::Maelstrom => "Maelstrom",
+            GrandCompany::TwinAdder => "Order of the Twin Adder",
+            GrandCompany::ImmortalFlames => "Immortal Flames",
+            GrandCompany::Unaffiliated => "Unaffiliated",
+        };
+
+        write!(f, "{}", gc)
+    }
+}
@@ -0,0 +1,68 @@
+use failure::Fail;
+use reqwest::Url;
+use std::convert::TryFrom;
+
+use crate::model::util::BASE_LODESTONE_URL;
+
+#[derive(Clone, Debug, Fail)]
+#[fail(display = "Invalid lodestone url '{}'", _0)]
+pub struct LodestoneIdParseError(String);
+
+/// The id of an entity on the lodestone, tagged with what kind of entity it is.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum LodestoneId {
+    Character(u32),
+    FreeCompany(u64),
+    Linkshell(u64),
+    /// PvP teams are identified by a hex string rather than a number.
+    PvpTeam(String),
+}
+
+impl LodestoneId {
+    /// The canonical url of the entity's page on the lodestone.
+    pub fn lodestone_url(&self) -> String {
+        let (kind, id) = match self {
+            LodestoneId::Character(id) => ("character", id.to_string()),
+            LodestoneId::FreeCompany(id) => ("freecompany", id.to_string()),
+            LodestoneId::Linkshell(id) => ("linkshell", id.to_string()),
+            LodestoneId::PvpTeam(id) => ("pvpteam", id.clone()),
+        };
+        format!("{}{}/{}/", BASE_LODESTONE_URL, kind, id)
+    }
+}
+
+/// Takes a lodestone url and extracts the id of the entity it points to.
+/// The url can be from any region, and the scheme and host are optional.
+///
+/// For example, `https://eu.finalfantasyxiv.com/lodestone/character/11908971/class_job/`
+/// will convert to `LodestoneId::Character(11908971)`
+impl TryFrom<&str> for LodestoneId {
+    type Error = LodestoneIdParseError;
+
+    fn try_from(url: &str) -> Result<Self, Self::Error> {
+        let err = || LodestoneIdParseError(url.into());
+
+        let path = url.split(&['?', '#'][..]).next().unwrap_or("");
+        let (_, path) = path.split_at(path.find("lodestone/").ok_or_else(err)? + "lodestone/".len());
+        let mut parts = path.split('/');
+
+        let kind = parts.next().ok_or_else(err)?;
+        let id = parts.next().ok_or_else(err)?;
+
+        match kind {
+            "character" => Ok(LodestoneId::Character(id.parse().map_err(|_| err())?)),
+            "freecompany" => Ok(LodestoneId::FreeCompany(id.parse().map_err(|_| err())?)),
+            "linkshell" => Ok(LodestoneId::Linkshell(id.parse().map_err(|_| err())?)),
+            "pvpteam" if !id.is_empty() && id.chars().all(|ch| ch.is_ascii_hexdigit()) => Ok(LodestoneId::PvpTeam(id.into())),
+            _ => Err(err()),
+        }
+    }
+}
+
+impl TryFrom<&Url> for LodestoneId {
+    type Error = LodestoneIdParseError;
+
+    fn try_from(url: &Url) -> Result<Self, Self::Error> {
+        Self::try_from(url.as_str())
+    }
+}
@@ -0,0 +1,76 @@
+use failure::Error;
+
+use crate::model::util::fetch_bytes;
+
+/// The suffix the lodestone gives the small, square avatar.
+static AVATAR_SUFFIX: &str = "c0_96x96.jpg";
+/// The suffix the lodestone gives the full length portrait.
+static PORTRAIT_SUFFIX: &str = "l0_640x873.jpg";
+
+/// The sizes a character's picture is served in.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum ImageSize {
+    /// The 96x96 face shown in search results and on the nameplate.
+    Avatar,
+    /// The 640x873 full length picture shown on the profile page.
+    Portrait,
+}
+
+impl ImageSize {
+    fn suffix(self) -> &'static str {
+        match self {
+            ImageSize::Avatar => AVATAR_SUFFIX,
+            ImageSize::Portrait => PORTRAIT_SUFFIX,
+        }
+    }
+}
+
+/// The pictures of a character.
+///
+/// Every size is served from the same path, with only the suffix of
+/// the file name changing, so any one url is enough to find the others.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct CharacterImages {
+    /// The url of the 96x96 face.
+    pub avatar: String,
+    /// The url of the full length picture.
+    pub portrait: String,
+}
+
+impl CharacterImages {
+    /// Derives every size from the url of any one of them.
+    ///
+    /// Returns None if the url does not end in a known size.
+    pub fn from_url(url: &str) -> Option<Self> {
+        Some(Self {
+            avatar: Self::resize(url, ImageSize::Avatar)?,
+            portrait: Self::resize(url, ImageSize::Portrait)?,
+        })
+    }
+
+    /// The url of the picture in a given size.
+    pub fn url(&self, size: ImageSize) -> &str {
+        match size {
+            ImageSize::Avatar => &self.avatar,
+            ImageSize::Portrait => &self.portrait,
+        }
+    }
+
+    /// Downloads the picture in a given size, returning the raw image bytes.
+    pub fn download(&self, size: ImageSize) -> Result<Vec<u8>, Error> {
+        fetch_bytes(self.url(size))
+    }
+
+    /// Swaps the size suffix of a picture's url, keeping any query string.
+    fn resize(url: &str, size: ImageSize) -> Option<String> {
+        let (path, query) = match url.find('?') {
+            Some(index) => url.split_at(index),
+            None => (url, ""),
+        };
+
+        let base = [AVATAR_SUFFIX, PORTRAIT_SUFFIX].iter()
+            .find_map(|suffix| path.strip_suffix(suffix))?;
+
+        Some(format!("{}{}{}", base, size.suffix(), query))
+    }
+}
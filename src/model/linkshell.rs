@@ -0,0 +1,220 @@
+use failure::{Error, Fail};
+use select::document::Document;
+use select::predicate::{Class, Name};
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::diagnostics::parse_or_dump;
+use crate::model::{
+    datacenter::Datacenter,
+    free_company::parse_world_text,
+    id::LodestoneId,
+    member::MemberEntry,
+    resource::LodestoneResource,
+    server::Server,
+    util::{ensure_node, fetch_html},
+};
+use crate::pagination::{Items, Page, Paginated, Pager, parse_page_numbers};
+
+#[derive(Clone, Debug, Fail)]
+#[fail(display = "Invalid linkshell rank string '{}'", _0)]
+pub struct LinkshellRankParseError(String);
+
+/// A member's standing within a linkshell.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum LinkshellRank {
+    Member,
+    Leader,
+    Master,
+}
+
+/// Case insensitive FromStr impl for linkshell ranks.
+impl FromStr for LinkshellRank {
+    type Err = LinkshellRankParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match &*s.trim().to_uppercase() {
+            "MEMBER" => Ok(LinkshellRank::Member),
+            "LEADER" => Ok(LinkshellRank::Leader),
+            "MASTER" => Ok(LinkshellRank::Master),
+            x => Err(LinkshellRankParseError(x.into())),
+        }
+    }
+}
+
+impl fmt::Display for LinkshellRank {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rank = match *self {
+            LinkshellRank::Member => "Member",
+            LinkshellRank::Leader => "Leader",
+            LinkshellRank::Master => "Master",
+        };
+        write!(f, "{}", rank)
+    }
+}
+
+/// A member of a linkshell, as listed on its page.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct LinkshellMember {
+    /// The id, name, and world shared with other rosters.
+    pub entry: MemberEntry,
+    /// The member's rank within the linkshell.
+    pub rank: LinkshellRank,
+}
+
+impl AsRef<MemberEntry> for LinkshellMember {
+    fn as_ref(&self) -> &MemberEntry {
+        &self.entry
+    }
+}
+
+/// Holds the data for a linkshell retrieved via Lodestone.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Linkshell {
+    /// The lodestone's id for the linkshell.
+    pub id: u64,
+    /// The linkshell's name.
+    pub name: String,
+    /// Which server the linkshell was formed on.
+    pub world: Server,
+    /// Which datacenter the linkshell's server belongs to.
+    pub datacenter: Datacenter,
+    /// How many members the linkshell has, as shown on its page.
+    pub member_count: u32,
+    /// Every member of the linkshell.
+    pub members: Vec<LinkshellMember>,
+}
+
+impl Linkshell {
+    /// Gets a linkshell given its lodestone id, along with every page
+    /// of its member list.
+    pub fn get(id: u64) -> Result<Self, Error> {
+        let pages = LinkshellMemberPages { id };
+        let html = fetch_html(&pages.url(1))?;
+        let (mut linkshell, total_pages) = parse_or_dump(&format!("linkshell-{}", id), &html, |doc| {
+            let (_, total_pages) = parse_page_numbers(doc);
+            Ok((Self::parse(id, doc)?, total_pages))
+        })?;
+
+        for page in 2..=total_pages {
+            linkshell.members.extend(pages.fetch_page(page)?.items);
+        }
+
+        Ok(linkshell)
+    }
+
+    /// Parses a linkshell from the HTML of the first page of its member
+    /// list, which has already been downloaded. Nothing is fetched, so
+    /// only the members on that page are included.
+    pub fn parse_from_html(id: u64, html: &str) -> Result<Self, Error> {
+        Self::parse(id, &Document::from(html))
+    }
+
+    /// The url of the linkshell's page on the lodestone.
+    pub fn lodestone_url(&self) -> String {
+        LodestoneId::Linkshell(self.id).lodestone_url()
+    }
+
+    /// Iterates over the members of a linkshell, fetching each page of its
+    /// member list only once the members before it have been read.
+    ///
+    /// Large linkshells span many pages. To wait between pages, use
+    /// `member_pages(id).delay(..).items()` instead.
+    pub fn members_iter(id: u64) -> Items<LinkshellMemberPages> {
+        Self::member_pages(id).items()
+    }
+
+    /// Creates a `Pager` over a linkshell's member list, which fetches
+    /// one page of members at a time as they are requested.
+    pub fn member_pages(id: u64) -> Pager<LinkshellMemberPages> {
+        Pager::new(LinkshellMemberPages { id })
+    }
+
+    pub(crate) fn parse(id: u64, doc: &Document) -> Result<Self, Error> {
+        let (world, datacenter) = parse_world_text(&ensure_node!(doc, Class("heading__linkshell__world")).text())?;
+
+        Ok(Self {
+            id,
+            name: ensure_node!(doc, Class("heading__linkshell__name")).text().trim().to_string(),
+            world,
+            datacenter,
+            member_count: Self::parse_member_count(doc)?,
+            members: parse_member_list(doc)?,
+        })
+    }
+
+    fn parse_member_count(doc: &Document) -> Result<u32, Error> {
+        //  Reads e.g. "42 Total".
+        let text = ensure_node!(doc, Class("parts__total")).text();
+        Ok(text
+            .replace(",", "")
+            .split(|ch: char| !ch.is_ascii_digit())
+            .find(|digits| !digits.is_empty())
+            .unwrap_or_default()
+            .parse::<u32>()?)
+    }
+}
+
+impl LodestoneResource for Linkshell {
+    type Id = u64;
+
+    fn fetch(id: u64) -> Result<Self, Error> {
+        Self::get(id)
+    }
+}
+
+/// The pages of a linkshell's member list.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LinkshellMemberPages {
+    id: u64,
+}
+
+impl LinkshellMemberPages {
+    fn url(&self, page: u32) -> String {
+        format!("{}?page={}", LodestoneId::Linkshell(self.id).lodestone_url(), page)
+    }
+}
+
+impl Paginated for LinkshellMemberPages {
+    type Item = LinkshellMember;
+
+    fn fetch_page(&self, page: u32) -> Result<Page<LinkshellMember>, Error> {
+        let html = fetch_html(&self.url(page))?;
+
+        parse_or_dump(&format!("linkshell-{}-page-{}", self.id, page), &html, |doc| {
+            let (current_page, total_pages) = parse_page_numbers(doc);
+
+            Ok(Page {
+                items: parse_member_list(doc)?,
+                current_page,
+                total_pages,
+            })
+        })
+    }
+}
+
+/// Parses the entries of a linkshell member page. Members without a
+/// rank badge are plain members.
+pub(crate) fn parse_member_list(doc: &Document) -> Result<Vec<LinkshellMember>, Error> {
+    let mut members = Vec::new();
+
+    for node in doc.find(Class("entry__bg")) {
+        let entry = match MemberEntry::parse(&node)? {
+            Some(entry) => entry,
+            None => continue,
+        };
+
+        let rank = match node.find(Class("entry__chara_info__linkshell")).next() {
+            Some(info) => LinkshellRank::from_str(&info.find(Name("span")).next().map(|span| span.text()).unwrap_or_default())?,
+            None => LinkshellRank::Member,
+        };
+
+        members.push(LinkshellMember {
+            entry,
+            rank,
+        });
+    }
+
+    Ok(members)
+}
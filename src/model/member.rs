@@ -0,0 +1,64 @@
+use failure::Error;
+use select::node::Node;
+use select::predicate::{Class, Name};
+
+use std::convert::TryFrom;
+
+use crate::model::{
+    datacenter::Datacenter,
+    free_company::parse_world_text,
+    id::LodestoneId,
+    server::Server,
+    util::ensure_node,
+};
+
+/// The part of a roster entry that is the same for every kind of group,
+/// whether a free company, a linkshell, or a PvP team.
+///
+/// Each group's member type holds one of these alongside the fields only
+/// that group has, such as its rank, and implements `AsRef<MemberEntry>`
+/// so that rosters can be handled uniformly.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct MemberEntry {
+    /// The member's lodestone id.
+    pub id: u32,
+    /// The member's name.
+    pub name: String,
+    /// Which server the member is on.
+    pub world: Server,
+    /// Which datacenter the member's server belongs to.
+    pub datacenter: Datacenter,
+    /// The url of the member's avatar, if the roster shows one.
+    pub avatar: Option<String>,
+}
+
+impl AsRef<MemberEntry> for MemberEntry {
+    fn as_ref(&self) -> &MemberEntry {
+        self
+    }
+}
+
+impl MemberEntry {
+    /// Reads the shared fields of a roster entry. Entries that do not link
+    /// to a character, such as headings, give None.
+    pub(crate) fn parse(entry: &Node) -> Result<Option<Self>, Error> {
+        let id = match LodestoneId::try_from(entry.attr("href").unwrap_or_default()) {
+            Ok(LodestoneId::Character(id)) => id,
+            _ => return Ok(None),
+        };
+
+        let (world, datacenter) = parse_world_text(&ensure_node!(entry, Class("entry__world")).text())?;
+
+        Ok(Some(MemberEntry {
+            id,
+            name: ensure_node!(entry, Class("entry__name")).text().trim().to_string(),
+            world,
+            datacenter,
+            avatar: entry.find(Class("entry__chara__face"))
+                .next()
+                .and_then(|face| face.find(Name("img")).next())
+                .and_then(|img| img.attr("src"))
+                .map(|src| src.to_string()),
+        }))
+    }
+}
@@ -0,0 +1,92 @@
+use failure::Error;
+use select::document::Document;
+use select::predicate::Class;
+
+use crate::diagnostics::parse_or_dump;
+use crate::model::profile::Section;
+use crate::model::util::{BASE_PROFILE_URL, ensure_node, ensure_public, fetch_mobile_html};
+
+/// A minion a character has acquired.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Minion {
+    /// The minion's name.
+    pub name: String,
+    /// The url of the minion's icon.
+    pub icon: String,
+}
+
+/// The minions a character has acquired, in the order the lodestone lists them.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Minions {
+    minions: Vec<Minion>,
+    total: usize,
+}
+
+impl Minions {
+    /// Gets the minions for a user given their lodestone user id.
+    pub fn get(user_id: u32) -> Result<Self, Error> {
+        let html = fetch_mobile_html(&format!("{}{}/minion/", BASE_PROFILE_URL, user_id))?;
+        parse_or_dump(&format!("minions-{}", user_id), &html, Self::parse)
+    }
+
+    /// Iterates over the minions.
+    pub fn iter(&self) -> impl Iterator<Item = &Minion> {
+        self.minions.iter()
+    }
+
+    /// The number of minions listed.
+    pub fn len(&self) -> usize {
+        self.minions.len()
+    }
+
+    /// The total number of minions acquired, as shown at the top of the
+    /// page. Falls back to the number of minions listed if it is missing.
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Whether no minions have been acquired.
+    pub fn is_empty(&self) -> bool {
+        self.minions.is_empty()
+    }
+
+    /// Whether a minion with the given name has been acquired. Case insensitive.
+    pub fn contains(&self, name: &str) -> bool {
+        self.minions.iter().any(|minion| minion.name.eq_ignore_ascii_case(name))
+    }
+
+    pub(crate) fn parse(doc: &Document) -> Result<Self, Error> {
+        ensure_public(doc, Section::Minions)?;
+        let mut minions = Vec::new();
+
+        for item in doc.find(Class("minion__list__item")) {
+            let name = ensure_node!(item, Class("minion__name")).text().trim().to_string();
+            let icon = ensure_node!(item, Class("minion__list__icon__image"))
+                .attr("src")
+                .unwrap_or_default()
+                .to_string();
+
+            minions.push(Minion { name, icon });
+        }
+
+        let total = doc.find(Class("minion__sort__total"))
+            .next()
+            .and_then(|node| node
+                .text()
+                .replace(",", "")
+                .split(|ch: char| !ch.is_ascii_digit())
+                .find_map(|part| part.parse::<usize>().ok()))
+            .unwrap_or(minions.len());
+
+        Ok(Minions { minions, total })
+    }
+}
+
+impl IntoIterator for Minions {
+    type Item = Minion;
+    type IntoIter = std::vec::IntoIter<Minion>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.minions.into_iter()
+    }
+}
@@ -0,0 +1,92 @@
+use failure::Error;
+use select::document::Document;
+use select::predicate::Class;
+
+use crate::diagnostics::parse_or_dump;
+use crate::model::profile::Section;
+use crate::model::util::{BASE_PROFILE_URL, ensure_node, ensure_public, fetch_mobile_html};
+
+/// A mount a character has acquired.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Mount {
+    /// The mount's name.
+    pub name: String,
+    /// The url of the mount's icon.
+    pub icon: String,
+}
+
+/// The mounts a character has acquired, in the order the lodestone lists them.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Mounts {
+    mounts: Vec<Mount>,
+    total: usize,
+}
+
+impl Mounts {
+    /// Gets the mounts for a user given their lodestone user id.
+    pub fn get(user_id: u32) -> Result<Self, Error> {
+        let html = fetch_mobile_html(&format!("{}{}/mount/", BASE_PROFILE_URL, user_id))?;
+        parse_or_dump(&format!("mounts-{}", user_id), &html, Self::parse)
+    }
+
+    /// Iterates over the mounts.
+    pub fn iter(&self) -> impl Iterator<Item = &Mount> {
+        self.mounts.iter()
+    }
+
+    /// The number of mounts listed.
+    pub fn len(&self) -> usize {
+        self.mounts.len()
+    }
+
+    /// The total number of mounts acquired, as shown at the top of the
+    /// page. Falls back to the number of mounts listed if it is missing.
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Whether no mounts have been acquired.
+    pub fn is_empty(&self) -> bool {
+        self.mounts.is_empty()
+    }
+
+    /// Whether a mount with the given name has been acquired. Case insensitive.
+    pub fn contains(&self, name: &str) -> bool {
+        self.mounts.iter().any(|mount| mount.name.eq_ignore_ascii_case(name))
+    }
+
+    pub(crate) fn parse(doc: &Document) -> Result<Self, Error> {
+        ensure_public(doc, Section::Mounts)?;
+        let mut mounts = Vec::new();
+
+        for item in doc.find(Class("mount__list__item")) {
+            let name = ensure_node!(item, Class("mount__name")).text().trim().to_string();
+            let icon = ensure_node!(item, Class("mount__list__icon__image"))
+                .attr("src")
+                .unwrap_or_default()
+                .to_string();
+
+            mounts.push(Mount { name, icon });
+        }
+
+        let total = doc.find(Class("mount__sort__total"))
+            .next()
+            .and_then(|node| node
+                .text()
+                .replace(",", "")
+                .split(|ch: char| !ch.is_ascii_digit())
+                .find_map(|part| part.parse::<usize>().ok()))
+            .unwrap_or(mounts.len());
+
+        Ok(Mounts { mounts, total })
+    }
+}
+
+impl IntoIterator for Mounts {
+    type Item = Mount;
+    type IntoIter = std::vec::IntoIter<Mount>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.mounts.into_iter()
+    }
+}
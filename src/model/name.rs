@@ -0,0 +1,80 @@
+use failure::Fail;
+
+/// The shortest a forename or surname can be.
+pub const MIN_PART_LENGTH: usize = 2;
+/// The longest a forename or surname can be.
+pub const MAX_PART_LENGTH: usize = 15;
+/// The longest a full name can be, including the space.
+pub const MAX_NAME_LENGTH: usize = 20;
+
+/// Represents the ways in which a character name can break the naming rules.
+#[derive(Clone, Debug, Eq, Fail, PartialEq)]
+pub enum NameValidationError {
+    /// A name must be exactly a forename and a surname separated by one space.
+    #[fail(display = "Name must be a forename and a surname, found {} part(s)", _0)]
+    WrongPartCount(usize),
+    /// A forename or surname is shorter than `MIN_PART_LENGTH`.
+    #[fail(display = "'{}' is shorter than 2 characters", _0)]
+    PartTooShort(String),
+    /// A forename or surname is longer than `MAX_PART_LENGTH`.
+    #[fail(display = "'{}' is longer than 15 characters", _0)]
+    PartTooLong(String),
+    /// The full name is longer than `MAX_NAME_LENGTH`.
+    #[fail(display = "Name is longer than 20 characters")]
+    NameTooLong,
+    /// A character other than a letter, hyphen, or apostrophe was used.
+    #[fail(display = "Invalid character '{}'", _0)]
+    InvalidCharacter(char),
+    /// A hyphen or apostrophe starts or ends a part, or is next to another one.
+    #[fail(display = "Misplaced hyphen or apostrophe in '{}'", _0)]
+    MisplacedPunctuation(String),
+}
+
+/// Checks a full character name against the game's naming rules, so that
+/// bad input can be rejected before a search is sent to the lodestone.
+///
+/// A valid name is a forename and a surname separated by a single space.
+/// Each part is 2 to 15 letters long, and may contain hyphens or apostrophes
+/// between letters. The whole name, including the space, is at most 20 characters.
+pub fn validate_name(name: &str) -> Result<(), NameValidationError> {
+    let parts = name.split(' ').collect::<Vec<&str>>();
+
+    if parts.len() != 2 {
+        return Err(NameValidationError::WrongPartCount(parts.len()));
+    }
+
+    if let Some(ch) = name.chars().find(|ch| !(ch.is_ascii_alphabetic() || *ch == ' ' || is_punctuation(*ch))) {
+        return Err(NameValidationError::InvalidCharacter(ch));
+    }
+
+    for part in &parts {
+        let len = part.chars().count();
+
+        if len < MIN_PART_LENGTH {
+            return Err(NameValidationError::PartTooShort(part.to_string()));
+        }
+
+        if len > MAX_PART_LENGTH {
+            return Err(NameValidationError::PartTooLong(part.to_string()));
+        }
+
+        let chars = part.chars().collect::<Vec<char>>();
+        let misplaced = is_punctuation(chars[0])
+            || is_punctuation(chars[len - 1])
+            || chars.windows(2).any(|pair| is_punctuation(pair[0]) && is_punctuation(pair[1]));
+
+        if misplaced {
+            return Err(NameValidationError::MisplacedPunctuation(part.to_string()));
+        }
+    }
+
+    if name.chars().count() > MAX_NAME_LENGTH {
+        return Err(NameValidationError::NameTooLong);
+    }
+
+    Ok(())
+}
+
+fn is_punctuation(ch: char) -> bool {
+    ch == '-' || ch == '\''
+}
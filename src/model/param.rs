@@ -0,0 +1,90 @@
+use failure::Error;
+use select::document::Document;
+use select::predicate::{Class, Name};
+
+use crate::model::{
+    profile::SearchError,
+    util::ensure_node,
+};
+
+/// The prefix of the class that labels each value in the parameter block.
+/// It is followed by the value's name and the page's locale, e.g.
+/// `character__param__text__mp--en-us`.
+static PARAM_CLASS_PREFIX: &str = "character__param__text__";
+
+/// The second bar shown under a character's HP, which depends on the
+/// kind of class they have equipped.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum SecondaryParam {
+    /// Max MP, shown for combat classes.
+    Mp(u32),
+    /// Max GP, shown for gatherers.
+    Gp(u32),
+    /// Max CP, shown for crafters.
+    Cp(u32),
+}
+
+impl SecondaryParam {
+    /// The value regardless of which kind it is.
+    pub fn value(self) -> u32 {
+        match self {
+            SecondaryParam::Mp(value) | SecondaryParam::Gp(value) | SecondaryParam::Cp(value) => value,
+        }
+    }
+}
+
+/// The HP and secondary values shown above a character's attributes.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct CharacterParams {
+    /// Max HP.
+    pub hp: u32,
+    /// Max MP, GP or CP, depending on the equipped class.
+    pub secondary: SecondaryParam,
+}
+
+impl CharacterParams {
+    /// Max MP, if a combat class is equipped.
+    pub fn mp(&self) -> Option<u32> {
+        match self.secondary {
+            SecondaryParam::Mp(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Parses the parameter block of a profile page.
+    ///
+    /// Each value is identified by the name in its label's class, so
+    /// this works for every locale.
+    pub(crate) fn parse(doc: &Document) -> Result<Self, Error> {
+        let block = ensure_node!(doc, Class("character__param"));
+        let mut hp = None;
+        let mut secondary = None;
+
+        for item in block.find(Name("li")) {
+            let kind = item.descendants()
+                .filter_map(|node| node.attr("class"))
+                .flat_map(|classes| classes.split_whitespace())
+                .find_map(|class| class.strip_prefix(PARAM_CLASS_PREFIX))
+                .and_then(|name| name.split("--").next());
+
+            let kind = match kind {
+                Some(kind) => kind,
+                None => continue,
+            };
+            let value = ensure_node!(item, Name("span")).text().trim().replace(",", "").parse::<u32>()?;
+
+            match kind {
+                "hp" => hp = Some(value),
+                "mp" => secondary = Some(SecondaryParam::Mp(value)),
+                "gp" => secondary = Some(SecondaryParam::Gp(value)),
+                "cp" => secondary = Some(SecondaryParam::Cp(value)),
+                _ => continue,
+            }
+        }
+
+        match (hp, secondary) {
+            (Some(hp), Some(secondary)) => Ok(Self { hp, secondary }),
+            _ => Err(SearchError::InvalidData("character__param".into()).into()),
+        }
+    }
+}
@@ -1,17 +1,41 @@
 use failure::{Error, Fail, ensure};
+use reqwest::StatusCode;
 use select::document::Document;
-use select::predicate::{Class, Name};
+use select::predicate::{Class, Name, Predicate};
 
+use std::collections::hash_map::RandomState;
+use std::convert::TryFrom;
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+use std::panic;
 use std::str::FromStr;
+use std::sync::{Mutex, PoisonError};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
 
+use crate::diagnostics::dump_parse_failure;
 use crate::model::{
-    attribute::{Attribute, Attributes},
+    achievement::{AchievementSummary, Achievements},
+    attribute::{Attribute, AttributeCategory, Attributes},
+    blog::Blog,
     clan::Clan,
     class::{Classes, ClassInfo, ClassType},
+    datacenter::Datacenter,
+    equipment::{Equipment, EquippedItem, GearSlot, Glamour, Materia},
+    free_company::{FreeCompanyRef, parse_crest},
     gender::Gender, 
+    id::LodestoneId,
+    images::CharacterImages,
+    minion::Minions,
+    param::CharacterParams,
+    mount::Mounts,
+    pvp_team::PvpTeamRef,
     race::Race, 
+    resource::LodestoneResource,
     server::Server,
-    util::load_url
+    title::{Title, TitlePosition},
+    util::{ensure_node, fetch_status, load_html, status_error, text_with_breaks},
 };
 
 /// Represents ways in which a search over the HTML data might go wrong.
@@ -23,6 +47,43 @@ pub enum SearchError {
     /// A node was found, but the data inside it was malformed.
     #[fail(display = "Invalid data found while parsing '{}'", _0)]
     InvalidData(String),
+    /// The lodestone answered with a status that was not expected.
+    #[fail(display = "Unexpected status code {}", _0)]
+    UnexpectedStatus(u16),
+    /// The page does not exist, e.g. because the character was deleted.
+    #[fail(display = "Page not found")]
+    NotFound,
+    /// The lodestone is down for maintenance.
+    #[fail(display = "The lodestone is under maintenance")]
+    Maintenance,
+    /// Too many requests have been made; wait before trying again.
+    #[fail(display = "Rate limited by the lodestone")]
+    RateLimited,
+    /// The lodestone failed to serve the page.
+    #[fail(display = "Server error with status code {}", _0)]
+    ServerError(u16),
+    /// The character has hidden a section of their profile.
+    #[fail(display = "The character's {} are private", _0)]
+    SectionPrivate(Section),
+}
+
+/// The parts of a profile that a character can choose to hide.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Section {
+    Achievements,
+    Mounts,
+    Minions,
+}
+
+impl fmt::Display for Section {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Section::Achievements => "achievements",
+            Section::Mounts => "mounts",
+            Section::Minions => "minions",
+        };
+        write!(f, "{}", name)
+    }
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
@@ -32,30 +93,23 @@ struct CharInfo {
     gender: Gender,
 }
 
-/// Takes a Document and a search expression, and will return
-/// a `SearchError` if it is not found. Otherwise it will return
-/// the found node.
-macro_rules! ensure_node {
-    ($doc:ident, $search:expr) => {{
-        ensure_node!($doc, $search, 0)
-    }};
-    
-    ($doc:ident, $search:expr, $nth:expr) => {{
-        let node = $doc.find($search).nth($nth);
-        ensure!(node.is_some(), SearchError::NodeNotFound(stringify!($search).to_string() + "(" + stringify!($nth) + ")"));
-        node.unwrap()
-    }};
-}
-
 /// Holds all the data for a profile retrieved via Lodestone.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Profile {
     /// The id associated with the profile
     pub user_id: u32,
     /// The profile's associated Free Company
-    pub free_company: Option<String>,
+    pub free_company: Option<FreeCompanyRef>,
+    /// The PvP team the character belongs to.
+    pub pvp_team: Option<PvpTeamRef>,
     /// The character's in-game name.
     pub name: String,
+    /// The title shown on the character's nameplate, if any.
+    pub title: Option<Title>,
+    /// The character's self-introduction, with line breaks as `\n`.
+    pub bio: Option<String>,
+    /// The character's avatar and portrait.
+    pub images: Option<CharacterImages>,
     /// The character's nameday
     pub nameday: String,
     /// The character's guardian
@@ -64,20 +118,77 @@ pub struct Profile {
     pub city_state: String,
     /// Which server the character is in.
     pub server: Server,
+    /// Which datacenter the character's server belongs to.
+    pub datacenter: Datacenter,
     /// What race the character is.
     pub race: Race,
     /// One of the two clans associated with their race.
     pub clan: Clan,
     /// Character's gender.
     pub gender: Gender,
-    /// Max HP.
-    pub hp: u32,
-    /// Max MP.
-    pub mp: u32,
+    /// Max HP, and max MP, GP or CP depending on the equipped class.
+    pub params: CharacterParams,
     /// A list of attributes and their values.
     pub attributes: Attributes,
-    /// A list of classes and their corresponding levels.
-    classes: Classes,
+    /// The items the character currently has equipped.
+    pub equipment: Equipment,
+    /// The class or job the character currently has equipped, read from
+    /// the icon next to their level, or from their gear if the icon could
+    /// not be matched, such as when the class page was not fetched.
+    pub active_class: Option<ClassType>,
+    /// The level shown next to the equipped class or job.
+    pub active_level: Option<u32>,
+    /// A list of classes and their corresponding levels, if they were fetched.
+    classes: Option<Classes>,
+    /// The pages this profile was parsed from.
+    #[cfg(feature = "raw-html")]
+    pub raw_html: RawHtml,
+}
+
+/// The HTML of the pages a profile was parsed from, kept around so
+/// that a mis-parsed profile can be reported along with its source.
+#[cfg(feature = "raw-html")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RawHtml {
+    /// The main profile page.
+    pub profile: String,
+    /// The class_job subpage, if it was fetched.
+    pub class_job: Option<String>,
+}
+
+/// Controls which pages are downloaded when getting a profile.
+///
+/// Every page is fetched by default. Callers that only need a
+/// character's identity can skip the class page to halve the number of
+/// requests, in which case the profile has no class information.
+/// To get only the classes, use `Classes::fetch` instead.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct ProfileOptions {
+    classes: bool,
+}
+
+impl Default for ProfileOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProfileOptions {
+    /// Options that fetch every page.
+    pub fn new() -> Self {
+        Self { classes: true }
+    }
+
+    /// Sets whether the class_job page is fetched.
+    pub fn classes(mut self, classes: bool) -> Self {
+        self.classes = classes;
+        self
+    }
+
+    /// Gets a profile with these options.
+    pub fn get(&self, user_id: u32) -> Result<Profile, Error> {
+        Profile::get_with_options(user_id, self)
+    }
 }
 
 impl Profile {
@@ -86,28 +197,142 @@ impl Profile {
     /// If you don't have the id, it is possible to use a 
     /// `SearchBuilder` in order to find their profile directly.
     pub fn get(user_id: u32) -> Result<Self, Error> {
-        let main_doc = load_url(user_id, None)?;
-        let classes_doc = load_url(user_id, Some("class_job"))?;
+        Self::get_with_options(user_id, &ProfileOptions::new())
+    }
+
+    /// Gets a profile, only fetching the pages asked for by the options.
+    ///
+    /// The class page is downloaded on a second thread while the main
+    /// page is, so fetching both takes about as long as fetching one.
+    pub fn get_with_options(user_id: u32, options: &ProfileOptions) -> Result<Self, Error> {
+        let (main_html, classes_html) = thread::scope(|scope| {
+            let classes = match options.classes {
+                true => Some(scope.spawn(|| load_html(user_id, Some("class_job")))),
+                false => None,
+            };
+            let main = load_html(user_id, None);
+            let classes = classes
+                .map(|handle| handle.join().unwrap_or_else(|e| panic::resume_unwind(e)))
+                .transpose();
+            (main, classes)
+        });
+        let (main_html, classes_html) = (main_html?, classes_html?);
+
+        Self::parse_pages(user_id, &main_html, classes_html.as_deref()).inspect_err(|e| {
+            let mut pages = vec![("profile", main_html.as_str())];
+            if let Some(html) = &classes_html {
+                pages.push(("class_job", html.as_str()));
+            }
+            dump_parse_failure(&format!("profile-{}", user_id), &pages, e);
+        })
+    }
+
+    /// Gets many profiles, using up to `max_concurrency` threads at once.
+    ///
+    /// Each thread waits `delay`, plus up to half as long again at random,
+    /// between its requests so as not to hammer the lodestone. Results are
+    /// returned in the same order as the ids, and one failing profile does
+    /// not stop the others from being fetched.
+    pub fn get_many(ids: &[u32], max_concurrency: usize, delay: Duration) -> Vec<Result<Self, Error>> {
+        let next = AtomicUsize::new(0);
+        let results = Mutex::new((0..ids.len()).map(|_| None).collect::<Vec<_>>());
+
+        thread::scope(|scope| {
+            for _ in 0..max_concurrency.clamp(1, ids.len().max(1)) {
+                scope.spawn(|| {
+                    let mut first = true;
+                    loop {
+                        let index = next.fetch_add(1, Ordering::SeqCst);
+                        let id = match ids.get(index) {
+                            Some(id) => *id,
+                            None => break,
+                        };
+
+                        if !first {
+                            thread::sleep(delay + jitter(delay / 2));
+                        }
+                        first = false;
+
+                        let result = Self::get(id);
+                        results.lock().unwrap_or_else(PoisonError::into_inner)[index] = Some(result);
+                    }
+                });
+            }
+        });
+
+        results.into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+            .into_iter()
+            .map(|result| result.expect("every id is fetched before the threads finish"))
+            .collect()
+    }
+
+    /// Checks whether a character exists, without downloading or parsing
+    /// its profile.
+    ///
+    /// Only the status code of the profile page is requested, so this is
+    /// much cheaper than `get` when validating ids in bulk. Statuses other
+    /// than success or not found, such as during maintenance, are errors;
+    /// see `status_error` for which.
+    pub fn exists(user_id: u32) -> Result<bool, Error> {
+        let url = LodestoneId::Character(user_id).lodestone_url();
+        let status = fetch_status(&url)?;
+        match status {
+            StatusCode::NOT_FOUND => Ok(false),
+            status if status.is_success() => Ok(true),
+            status => Err(status_error(status, "")),
+        }
+    }
+
+    /// Parses a profile from HTML that has already been downloaded, for
+    /// callers with their own HTTP stack or a cache of pages.
+    ///
+    /// `main_html` is the character's main page and `classes_html` its
+    /// `class_job/` page. Nothing is fetched, and failures are not dumped
+    /// to the diagnostics directory.
+    pub fn parse_from_html(user_id: u32, main_html: &str, classes_html: &str) -> Result<Self, Error> {
+        Self::parse_pages(user_id, main_html, Some(classes_html))
+    }
+
+    fn parse_pages(user_id: u32, main_html: &str, classes_html: Option<&str>) -> Result<Self, Error> {
+        let main_doc = Document::from(main_html);
 
         //  Holds the string for Race, Clan, and Gender in that order
         let char_info = Self::parse_char_info(&main_doc)?;
-        let (hp, mp) = Self::parse_char_param(&main_doc)?;
+        let (server, datacenter) = Self::parse_world(&main_doc)?;
+        let equipment = Self::parse_equipment(&main_doc)?;
+        let classes = classes_html
+            .map(|html| Classes::parse(&Document::from(html)))
+            .transpose()?;
+        let active_class = Self::parse_active_class(&main_doc, classes.as_ref(), &equipment);
 
         Ok(Self {
             user_id,
-            free_company: Self::parse_free_company(&main_doc),
+            free_company: Self::parse_free_company(&main_doc)?,
+            pvp_team: Self::parse_pvp_team(&main_doc)?,
             name: Self::parse_name(&main_doc)?,
+            title: Self::parse_title(&main_doc)?,
+            bio: Self::parse_bio(&main_doc),
+            images: Self::parse_images(&main_doc),
             nameday: Self::parse_nameday(&main_doc)?,
             guardian: Self::parse_guardian(&main_doc)?,
             city_state: Self::parse_city_state(&main_doc)?,
-            server: Self::parse_server(&main_doc)?,
+            server,
+            datacenter,
             race: char_info.race,
             clan: char_info.clan,
             gender: char_info.gender,
-            hp,
-            mp,
+            params: CharacterParams::parse(&main_doc)?,
             attributes: Self::parse_attributes(&main_doc)?,
-            classes: Self::parse_classes(&classes_doc)?,
+            equipment,
+            active_class,
+            active_level: Self::parse_active_level(&main_doc),
+            classes,
+            #[cfg(feature = "raw-html")]
+            raw_html: RawHtml {
+                profile: main_html.to_string(),
+                class_job: classes_html.map(str::to_string),
+            },
         })
     }
 
@@ -124,27 +349,186 @@ impl Profile {
         }
     }
 
+    /// Whether this profile has unlocked a given class or job.
+    ///
+    /// Jobs are only unlocked once their soul crystal has been obtained,
+    /// while a base class counts as unlocked if either it or one of its
+    /// jobs has been. Limited jobs such as Blue Mage are unlocked once
+    /// they have been picked up at all.
+    pub fn has_unlocked(&self, class: ClassType) -> bool {
+        self.class_info(class).is_some()
+    }
+
     /// Gets this profile's data for a given class
     pub fn class_info(&self, class: ClassType) -> Option<ClassInfo> {
-        self.classes.get(class)
+        self.classes.as_ref()?.get(class)
     }
 
     /// Borrows the full map of classes, e.g. for iteration in calling code
-    pub fn all_class_info(&self) -> &Classes {
-        &self.classes
+    ///
+    /// Returns None if the class page was skipped with `ProfileOptions`.
+    pub fn all_class_info(&self) -> Option<&Classes> {
+        self.classes.as_ref()
     }
 
-    fn parse_free_company(doc: &Document) -> Option<String> {
-        match doc.find(Class("frame__chara__title")).next() {
-            Some(node) => Some(node.text()),
-            None => None,
-        }
+    /// The average item level of the character's equipped gear.
+    /// See `Equipment::average_item_level` for how this is calculated.
+    pub fn average_item_level(&self) -> Option<u32> {
+        self.equipment.average_item_level()
+    }
+
+    /// Fetches every achievement this character has earned.
+    /// This walks every page of the achievement list, so it is not done as part of `get`.
+    pub fn achievements(&self) -> Result<Achievements, Error> {
+        Achievements::get(self.user_id)
+    }
+
+    /// Fetches this character's achievement points and most recent
+    /// achievements, which only takes a single request.
+    pub fn achievement_summary(&self) -> Result<AchievementSummary, Error> {
+        AchievementSummary::get(self.user_id)
+    }
+
+    /// Fetches this character's public blog entries.
+    /// This walks every page of the blog, so it is not done as part of `get`.
+    pub fn blog(&self) -> Result<Blog, Error> {
+        Blog::get(self.user_id)
+    }
+
+    /// Fetches the mounts this character has acquired.
+    /// This is a separate request, so it is not done as part of `get`.
+    pub fn mounts(&self) -> Result<Mounts, Error> {
+        Mounts::get(self.user_id)
+    }
+
+    /// Fetches the minions this character has acquired.
+    /// This is a separate request, so it is not done as part of `get`.
+    pub fn minions(&self) -> Result<Minions, Error> {
+        Minions::get(self.user_id)
+    }
+
+    /// The url of this profile's page on the lodestone.
+    pub fn lodestone_url(&self) -> String {
+        LodestoneId::Character(self.user_id).lodestone_url()
+    }
+
+    /// A compact, human readable summary of the profile for posting in
+    /// chat, with the name and world on the first line and the levels of
+    /// every unlocked job on the second, highest first.
+    ///
+    /// For example:
+    /// ```text
+    /// Strawberry Custard - Famfrit [Primal]
+    /// BLM 70 · WAR 60 · RDM 50 · SAM 50 · SCH 33 · DRK 30 · AST 30 · MCH 30 · ...
+    /// ```
+    pub fn summary(&self) -> String {
+        let name = match &self.title {
+            Some(title) => title.with_name(&self.name),
+            None => self.name.clone(),
+        };
+
+        let jobs = self.classes.as_ref()
+            .map(Classes::sorted_by_level)
+            .unwrap_or_default();
+
+        let jobs = jobs.iter()
+            .map(|(kind, info)| format!("{} {}", kind.abbreviation(), info.level))
+            .collect::<Vec<_>>()
+            .join(" · ");
+
+        format!("{} - {} [{}]\n{}", name, self.server, self.datacenter, jobs)
+    }
+
+    fn parse_free_company(doc: &Document) -> Result<Option<FreeCompanyRef>, Error> {
+        let block = match doc.find(Class("character__freecompany__name")).next() {
+            Some(block) => block,
+            None => return Ok(None),
+        };
+
+        let link = ensure_node!(block, Name("a"));
+        let href = link.attr("href").unwrap_or_default();
+        let id = match LodestoneId::try_from(href)? {
+            LodestoneId::FreeCompany(id) => id,
+            _ => return Err(SearchError::InvalidData("character__freecompany__name".into()).into()),
+        };
+
+        let crest = parse_crest(doc, "character__freecompany__crest__image");
+
+        Ok(Some(FreeCompanyRef {
+            id,
+            name: link.text().trim().to_string(),
+            crest,
+        }))
+    }
+
+    fn parse_pvp_team(doc: &Document) -> Result<Option<PvpTeamRef>, Error> {
+        let block = match doc.find(Class("character__pvpteam__name")).next() {
+            Some(block) => block,
+            None => return Ok(None),
+        };
+
+        let link = ensure_node!(block, Name("a"));
+        let href = link.attr("href").unwrap_or_default();
+        let id = match LodestoneId::try_from(href)? {
+            LodestoneId::PvpTeam(id) => id,
+            _ => return Err(SearchError::InvalidData("character__pvpteam__name".into()).into()),
+        };
+
+        let crest = parse_crest(doc, "character__pvpteam__crest__image");
+
+        Ok(Some(PvpTeamRef {
+            id,
+            name: link.text().trim().to_string(),
+            crest,
+        }))
     }
 
     fn parse_name(doc: &Document) -> Result<String, Error> {
         Ok(ensure_node!(doc, Class("frame__chara__name")).text())
     }
 
+    fn parse_title(doc: &Document) -> Result<Option<Title>, Error> {
+        let title = match doc.find(Class("frame__chara__title")).next() {
+            Some(node) => node,
+            None => return Ok(None),
+        };
+
+        let name = title.text().trim().to_string();
+        if name.is_empty() {
+            return Ok(None);
+        }
+
+        //  The title node comes before the name node for prefix titles, and after it otherwise.
+        let name_node = ensure_node!(doc, Class("frame__chara__name"));
+        let position = if title.index() < name_node.index() {
+            TitlePosition::Prefix
+        } else {
+            TitlePosition::Suffix
+        };
+
+        Ok(Some(Title { name, position }))
+    }
+
+    fn parse_bio(doc: &Document) -> Option<String> {
+        let node = doc.find(Class("character__selfintroduction")).next()?;
+        let bio = text_with_breaks(&node);
+
+        //  Characters without a self-introduction show a single dash.
+        let bio = bio.trim();
+        if bio.is_empty() || bio == "-" {
+            None
+        } else {
+            Some(bio.to_string())
+        }
+    }
+
+    fn parse_images(doc: &Document) -> Option<CharacterImages> {
+        ["character__detail__image", "frame__chara__face"].iter()
+            .filter_map(|class| doc.find(Class(*class).descendant(Name("img"))).next())
+            .filter_map(|img| img.attr("src"))
+            .find_map(CharacterImages::from_url)
+    }
+
     fn parse_nameday(doc: &Document) -> Result<String, Error> {
         Ok(ensure_node!(doc, Class("character-block__birth")).text())
     }
@@ -157,13 +541,17 @@ impl Profile {
         Ok(ensure_node!(doc, Class("character-block__name"), 2).text())
     }
 
-    fn parse_server(doc: &Document) -> Result<Server, Error> {
+    fn parse_world(doc: &Document) -> Result<(Server, Datacenter), Error> {
         let text = ensure_node!(doc, Class("frame__chara__world")).text();
-        let server = text.split("\u{A0}").next();
+        let mut parts = text.split('\u{A0}');
 
+        let server = parts.next();
         ensure!(server.is_some(), SearchError::InvalidData("Could not find server string.".into()));
 
-        Ok(Server::from_str(&server.unwrap())?)
+        let datacenter = parts.next();
+        ensure!(datacenter.is_some(), SearchError::InvalidData("Could not find datacenter string.".into()));
+
+        Ok((Server::from_str(server.unwrap())?, Datacenter::from_str(datacenter.unwrap())?))
     }
 
     fn parse_char_info(doc: &Document) -> Result<CharInfo, Error> {
@@ -198,90 +586,142 @@ impl Profile {
         }
     }
 
-    fn parse_char_param(doc: &Document) -> Result<(u32, u32), Error> {
-        let attr_block = ensure_node!(doc, Class("character__param"));
-        let mut hp = None;
-        let mut mp = None;
-        for item in attr_block.find(Name("li")) {
-            if item.find(Class("character__param__text__hp--en-us")).count() == 1 {
-                hp = Some(ensure_node!(item, Name("span")).text().parse::<u32>()?);
-            } else if item.find(Class("character__param__text__mp--en-us")).count() == 1 {
-                mp = Some(ensure_node!(item, Name("span")).text().parse::<u32>()?);
-            } else {
-                continue
-            }
-        }
-        ensure!(hp.is_some() && mp.is_some(), SearchError::InvalidData("character__param".into()));
-        Ok((hp.unwrap(), mp.unwrap()))
-    }
-
     fn parse_attributes(doc: &Document) -> Result<Attributes, Error> {
         let block = ensure_node!(doc, Class("character__profile__data"));
         let mut attributes = Attributes::new();
-        for item in block.find(Name("tr")) {
+        let mut category = None;
+        //  Section headers and rows are visited in document order, so each
+        //  row is filed under the most recent header.
+        for item in block.find(Name("h3").or(Name("tr"))) {
+            if item.name() == Some("h3") {
+                category = item.text().parse::<AttributeCategory>().ok();
+                continue;
+            }
+
             let name = ensure_node!(item, Name("span")).text();
             let value = Attribute{
                 level: ensure_node!(item, Name("td")).text().parse::<u16>()?
             };
-            attributes.insert(name, value);
+            match category {
+                Some(category) => attributes.insert_in(category, name, value),
+                None => attributes.insert(name, value),
+            }
         }
         Ok(attributes)
     }
 
-    fn parse_classes(doc: &Document) -> Result<Classes, Error> {
-        let mut classes = Classes::new();
-
-        for list in doc.find(Class("character__content")).take(4) {
-            for item in list.find(Name("li")) {
-                let name = ensure_node!(item, Class("character__job__name")).text();
-                let classinfo = match ensure_node!(item, Class("character__job__level")).text().as_str() {
-                    "-" => None,
-                    level => {
-                        let text = ensure_node!(item, Class("character__job__exp")).text();
-                        let mut parts = text.split(" / ");
-                        let current_xp = parts.next();
-                        ensure!(current_xp.is_some(), SearchError::InvalidData("character__job__exp".into()));
-                        let max_xp = parts.next();
-                        ensure!(max_xp.is_some(), SearchError::InvalidData("character__job__exp".into()));
-                        Some(ClassInfo{
-                            level: level.parse()?,
-                            current_xp: match current_xp.unwrap() {
-                                "--" => None,
-                                value => Some(value.replace(",", "").parse()?)
-                            },
-                            max_xp: match max_xp.unwrap() {
-                                "--" => None,
-                                value => Some(value.replace(",", "").parse()?)
-                            },
-                        })
-                    }
-                };
-
-                //  For classes that have multiple titles (e.g., Paladin / Gladiator), grab the first one.
-                let name = name.split(" / ").next();
-                ensure!(name.is_some(), SearchError::InvalidData("character__job__name".into()));
-                let class = ClassType::from_str(&name.unwrap())?;
-
-                //  If the class added was a secondary job, then associated that level
-                //  with its lower level counterpart as well. This makes returning the
-                //  level for a particular grouping easier at the cost of memory.
-                match class {
-                    ClassType::Paladin => classes.insert(ClassType::Gladiator, classinfo),
-                    ClassType::Warrior => classes.insert(ClassType::Marauder, classinfo),
-                    ClassType::WhiteMage => classes.insert(ClassType::Conjurer, classinfo),
-                    ClassType::Monk => classes.insert(ClassType::Pugilist, classinfo),
-                    ClassType::Dragoon => classes.insert(ClassType::Lancer, classinfo),
-                    ClassType::Ninja => classes.insert(ClassType::Rogue, classinfo),
-                    ClassType::Bard => classes.insert(ClassType::Archer, classinfo),
-                    ClassType::BlackMage => classes.insert(ClassType::Thaumaturge, classinfo),
-                    ClassType::Summoner => classes.insert(ClassType::Arcanist, classinfo),
-                    _ => (),
-                }
-
-                classes.insert(class, classinfo);
-            }
+    /// Works out the equipped class or job from the icon shown next to
+    /// the character's name and level.
+    ///
+    /// The icon's url does not name the class, so it is matched against
+    /// the icons on the class_job page. When that page was skipped, or the
+    /// icon is missing, this falls back to `Equipment::active_class`, which
+    /// reads the soul crystal and main hand instead.
+    pub(crate) fn parse_active_class(doc: &Document, classes: Option<&Classes>, equipment: &Equipment) -> Option<ClassType> {
+        let from_icon = doc.find(Class("character__class_icon").descendant(Name("img")))
+            .next()
+            .and_then(|img| img.attr("src"))
+            .and_then(|icon| classes?.class_for_icon(icon));
+
+        from_icon.or_else(|| equipment.active_class())
+    }
+
+    fn parse_active_level(doc: &Document) -> Option<u32> {
+        //  Shown as e.g. "LEVEL 90", or "Lv. 90" in some languages.
+        let text = doc.find(Class("character__class__data").descendant(Name("p"))).next()?.text();
+        text.split_whitespace()
+            .filter_map(|word| word.trim_start_matches(|ch: char| !ch.is_ascii_digit()).parse().ok())
+            .next()
+    }
+
+    pub(crate) fn parse_equipment(doc: &Document) -> Result<Equipment, Error> {
+        let mut equipment = Equipment::new();
+
+        for slot in GearSlot::ALL.iter() {
+            let class = format!("icon-c--{}", slot.icon_index());
+            let node = match doc.find(Class(class.as_str())).next() {
+                Some(node) => node,
+                None => continue,
+            };
+
+            //  Empty slots still have an icon, but no item tooltip.
+            let name = match node.find(Class("db-tooltip__item__name")).next() {
+                Some(name) => name.text().trim().to_string(),
+                None => continue,
+            };
+
+            let category = ensure_node!(node, Class("db-tooltip__item__category")).text().trim().to_string();
+
+            let item_level = match node.find(Class("db-tooltip__item__level")).next() {
+                Some(level) => {
+                    let digits = level.text().chars().filter(|ch| ch.is_ascii_digit()).collect::<String>();
+                    Some(digits.parse::<u32>()?)
+                },
+                None => None,
+            };
+
+            //  Each socket holds the materia name followed by a span with its bonus.
+            //  Empty sockets have no text at all.
+            let materia = node.find(Class("db-tooltip__materia__txt"))
+                .filter_map(|socket| {
+                    let name = socket.children()
+                        .filter_map(|child| child.as_text())
+                        .map(|text| text.trim())
+                        .find(|text| !text.is_empty())?
+                        .to_string();
+                    let bonus = socket.find(Name("span")).next().map(|span| span.text().trim().to_string());
+
+                    Some(Materia {
+                        tier: Materia::parse_tier(&name),
+                        name,
+                        bonus,
+                    })
+                })
+                .collect();
+
+            let glamour_item = node.find(Class("db-tooltip__item__mirage"))
+                .next()
+                .and_then(|mirage| mirage.find(Name("p")).next())
+                .map(|p| p.text().trim().to_string())
+                .filter(|text| !text.is_empty());
+            let dye = node.find(Class("stain"))
+                .next()
+                .map(|stain| stain.text().trim().to_string())
+                .filter(|text| !text.is_empty());
+
+            let glamour = match (glamour_item, dye) {
+                (None, None) => None,
+                (item, dye) => Some(Glamour { item, dye }),
+            };
+
+            equipment.insert(EquippedItem {
+                slot: *slot,
+                name,
+                category,
+                item_level,
+                materia,
+                glamour,
+            });
         }
 
-        Ok(classes)
+        Ok(equipment)
+    }
+}
+
+/// A random duration of up to `max`, so that bulk requests do not
+/// arrive in lockstep.
+fn jitter(max: Duration) -> Duration {
+    let random = RandomState::new().build_hasher().finish();
+    match max.as_millis() as u64 {
+        0 => Duration::ZERO,
+        millis => Duration::from_millis(random % millis),
+    }
+}
+
+impl LodestoneResource for Profile {
+    type Id = u32;
+
+    fn fetch(id: u32) -> Result<Self, Error> {
+        Self::get(id)
     }
 }
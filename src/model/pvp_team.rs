@@ -0,0 +1,188 @@
+use failure::{Error, Fail};
+use select::document::Document;
+use select::predicate::{Class, Name};
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::diagnostics::parse_or_dump;
+use crate::model::{
+    datacenter::Datacenter,
+    free_company::parse_crest,
+    id::LodestoneId,
+    member::MemberEntry,
+    resource::LodestoneResource,
+    util::{ensure_node, fetch_html, parse_strftime_timestamp},
+};
+
+/// A reference to a PvP team, as shown on a member's profile.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct PvpTeamRef {
+    /// The lodestone's id for the team. Unlike other ids this is a hex string.
+    pub id: String,
+    /// The team's name.
+    pub name: String,
+    /// The urls of the images that are layered to make the team crest,
+    /// from the bottom layer to the top.
+    pub crest: Vec<String>,
+}
+
+impl PvpTeamRef {
+    /// The url of the team's page on the lodestone.
+    pub fn lodestone_url(&self) -> String {
+        LodestoneId::PvpTeam(self.id.clone()).lodestone_url()
+    }
+
+    /// Fetches the full PvP team this refers to.
+    pub fn fetch(&self) -> Result<PvpTeam, Error> {
+        PvpTeam::get(&self.id)
+    }
+}
+
+#[derive(Clone, Debug, Fail)]
+#[fail(display = "Invalid PvP team rank string '{}'", _0)]
+pub struct PvpTeamRankParseError(String);
+
+/// A member's standing within a PvP team.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum PvpTeamRank {
+    Member,
+    Leader,
+}
+
+/// Case insensitive FromStr impl for PvP team ranks.
+impl FromStr for PvpTeamRank {
+    type Err = PvpTeamRankParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match &*s.trim().to_uppercase() {
+            "MEMBER" => Ok(PvpTeamRank::Member),
+            "LEADER" => Ok(PvpTeamRank::Leader),
+            x => Err(PvpTeamRankParseError(x.into())),
+        }
+    }
+}
+
+impl fmt::Display for PvpTeamRank {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rank = match *self {
+            PvpTeamRank::Member => "Member",
+            PvpTeamRank::Leader => "Leader",
+        };
+        write!(f, "{}", rank)
+    }
+}
+
+/// A member of a PvP team, as listed on its page.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct PvpTeamMember {
+    /// The id, name, and world shared with other rosters.
+    pub entry: MemberEntry,
+    /// The member's rank within the team.
+    pub rank: PvpTeamRank,
+    /// How many matches the member has played with the team.
+    pub matches: u32,
+}
+
+impl AsRef<MemberEntry> for PvpTeamMember {
+    fn as_ref(&self) -> &MemberEntry {
+        &self.entry
+    }
+}
+
+/// Holds the data for a PvP team retrieved via Lodestone.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PvpTeam {
+    /// The lodestone's id for the team. Unlike other ids this is a hex string.
+    pub id: String,
+    /// The team's name.
+    pub name: String,
+    /// The datacenter the team belongs to.
+    pub datacenter: Datacenter,
+    /// The urls of the images that are layered to make the team crest,
+    /// from the bottom layer to the top.
+    pub crest: Vec<String>,
+    /// When the team was formed, as a Unix timestamp.
+    pub formed_at: Option<u64>,
+    /// Every member of the team. Teams are small enough to fit on one page.
+    pub members: Vec<PvpTeamMember>,
+}
+
+impl PvpTeam {
+    /// Gets a PvP team given its lodestone id.
+    pub fn get(id: &str) -> Result<Self, Error> {
+        let html = fetch_html(&LodestoneId::PvpTeam(id.to_string()).lodestone_url())?;
+        parse_or_dump(&format!("pvpteam-{}", id), &html, |doc| Self::parse(id, doc))
+    }
+
+    /// Parses a PvP team from the HTML of its page, which has already
+    /// been downloaded. Nothing is fetched.
+    pub fn parse_from_html(id: &str, html: &str) -> Result<Self, Error> {
+        Self::parse(id, &Document::from(html))
+    }
+
+    /// The url of the team's page on the lodestone.
+    pub fn lodestone_url(&self) -> String {
+        LodestoneId::PvpTeam(self.id.clone()).lodestone_url()
+    }
+
+    pub(crate) fn parse(id: &str, doc: &Document) -> Result<Self, Error> {
+        Ok(Self {
+            id: id.to_string(),
+            name: ensure_node!(doc, Class("entry__pvpteam__name--team")).text().trim().to_string(),
+            datacenter: ensure_node!(doc, Class("entry__pvpteam__name--dc")).text().parse::<Datacenter>()?,
+            crest: parse_crest(doc, "entry__pvpteam__crest__image"),
+            formed_at: doc.find(Class("entry__pvpteam__data--formed"))
+                .next()
+                .and_then(|node| node.find(Name("script")).next())
+                .and_then(|script| parse_strftime_timestamp(&script.text())),
+            members: parse_member_list(doc)?,
+        })
+    }
+}
+
+impl LodestoneResource for PvpTeam {
+    type Id = String;
+
+    fn fetch(id: String) -> Result<Self, Error> {
+        Self::get(&id)
+    }
+}
+
+/// Parses the entries of a PvP team's member list. The leader is marked
+/// with a badge, and every entry shows the member's match count.
+pub(crate) fn parse_member_list(doc: &Document) -> Result<Vec<PvpTeamMember>, Error> {
+    let mut members = Vec::new();
+
+    for node in doc.find(Class("entry__bg")) {
+        let entry = match MemberEntry::parse(&node)? {
+            Some(entry) => entry,
+            None => continue,
+        };
+
+        let rank = match node.find(Class("entry__chara_info__pvpteam")).next() {
+            Some(info) => PvpTeamRank::from_str(&info.find(Name("span")).next().map(|span| span.text()).unwrap_or_default())?,
+            None => PvpTeamRank::Member,
+        };
+
+        //  Reads e.g. "Matches: 42".
+        let matches = match node.find(Class("entry__freecompany__info")).next() {
+            Some(info) => info
+                .text()
+                .replace(",", "")
+                .split(|ch: char| !ch.is_ascii_digit())
+                .find(|digits| !digits.is_empty())
+                .unwrap_or("0")
+                .parse::<u32>()?,
+            None => 0,
+        };
+
+        members.push(PvpTeamMember {
+            entry,
+            rank,
+            matches,
+        });
+    }
+
+    Ok(members)
+}
@@ -0,0 +1,13 @@
+use failure::Error;
+
+/// A single entity that can be loaded from the lodestone by its id.
+///
+/// This allows code such as caches or trackers to be written once
+/// over every entity type instead of once per type.
+pub trait LodestoneResource: Sized {
+    /// The type used by the lodestone to identify this resource.
+    type Id;
+
+    /// Fetches and parses the resource with the given id.
+    fn fetch(id: Self::Id) -> Result<Self, Error>;
+}
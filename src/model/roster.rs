@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+
+use crate::model::free_company::FcMember;
+
+/// A member whose rank within the company changed between two rosters.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct RankChange {
+    /// The member as listed in the newer roster.
+    pub member: FcMember,
+    /// The name of the rank they held in the older roster.
+    pub previous_rank: String,
+}
+
+/// The differences between two snapshots of a free company's roster.
+///
+/// Members are matched by their lodestone id, so a member who changed
+/// their name or world is not counted as leaving and rejoining.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RosterDelta {
+    /// Members in the newer roster but not the older one, in the newer roster's order.
+    pub joined: Vec<FcMember>,
+    /// Members in the older roster but not the newer one, in the older roster's order.
+    pub left: Vec<FcMember>,
+    /// Members in both rosters whose rank name differs.
+    pub rank_changed: Vec<RankChange>,
+}
+
+impl RosterDelta {
+    /// Compares an older roster against a newer one.
+    pub fn between(old: &[FcMember], new: &[FcMember]) -> Self {
+        let old_by_id = old.iter().map(|member| (member.entry.id, member)).collect::<HashMap<_, _>>();
+        let new_by_id = new.iter().map(|member| (member.entry.id, member)).collect::<HashMap<_, _>>();
+
+        let mut delta = RosterDelta::default();
+
+        for member in new {
+            match old_by_id.get(&member.entry.id) {
+                None => delta.joined.push(member.clone()),
+                Some(previous) if previous.rank_name != member.rank_name => {
+                    delta.rank_changed.push(RankChange {
+                        member: member.clone(),
+                        previous_rank: previous.rank_name.clone(),
+                    });
+                }
+                Some(_) => {}
+            }
+        }
+
+        delta.left = old.iter()
+            .filter(|member| !new_by_id.contains_key(&member.entry.id))
+            .cloned()
+            .collect();
+
+        delta
+    }
+
+    /// Whether the two rosters had the same members with the same ranks.
+    pub fn is_empty(&self) -> bool {
+        self.joined.is_empty() && self.left.is_empty() && self.rank_changed.is_empty()
+    }
+}
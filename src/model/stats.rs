@@ -0,0 +1,91 @@
+use crate::model::attribute::{AttributeKind, Attributes};
+
+//  All formulas use the level 100 modifiers, so the results are only
+//  meaningful for characters at the level cap.
+
+/// The level 100 main stat modifier.
+const LEVEL_MAIN: i64 = 440;
+/// The level 100 sub stat modifier.
+const LEVEL_SUB: i64 = 420;
+/// The level 100 divisor.
+const LEVEL_DIV: i64 = 2780;
+
+/// The base recast of a weaponskill or spell, in milliseconds.
+pub const BASE_GCD: u32 = 2500;
+
+/// The chance of landing a critical hit, as a percentage.
+pub fn critical_hit_rate(critical_hit: u16) -> f64 {
+    (200 * (critical_hit as i64 - LEVEL_SUB)).div_euclid(LEVEL_DIV) as f64 / 10.0 + 5.0
+}
+
+/// The damage multiplier applied to critical hits, e.g. `1.4`.
+pub fn critical_hit_damage(critical_hit: u16) -> f64 {
+    ((200 * (critical_hit as i64 - LEVEL_SUB)).div_euclid(LEVEL_DIV) + 1400) as f64 / 1000.0
+}
+
+/// The chance of landing a direct hit, as a percentage.
+pub fn direct_hit_rate(direct_hit: u16) -> f64 {
+    (550 * (direct_hit as i64 - LEVEL_SUB)).div_euclid(LEVEL_DIV) as f64 / 10.0
+}
+
+/// The damage multiplier granted by determination, e.g. `1.1`.
+pub fn determination_multiplier(determination: u16) -> f64 {
+    ((140 * (determination as i64 - LEVEL_MAIN)).div_euclid(LEVEL_DIV) + 1000) as f64 / 1000.0
+}
+
+/// The damage and mitigation multiplier granted by tenacity, e.g. `1.05`.
+pub fn tenacity_multiplier(tenacity: u16) -> f64 {
+    ((112 * (tenacity as i64 - LEVEL_SUB)).div_euclid(LEVEL_DIV) + 1000) as f64 / 1000.0
+}
+
+/// The recast in seconds of an action with the given base recast in
+/// milliseconds, after applying skill or spell speed.
+///
+/// For the usual global cooldown, pass `BASE_GCD` as the base recast.
+pub fn gcd(speed: u16, base_recast: u32) -> f64 {
+    let modifier = 1000 - (130 * (speed as i64 - LEVEL_SUB)).div_euclid(LEVEL_DIV);
+    let recast = (modifier * base_recast as i64).div_euclid(1000);
+
+    recast.div_euclid(10) as f64 / 100.0
+}
+
+/// The derived values for a profile's attributes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DerivedStats {
+    /// Critical hit chance as a percentage.
+    pub critical_hit_rate: f64,
+    /// Critical hit damage multiplier.
+    pub critical_hit_damage: f64,
+    /// Direct hit chance as a percentage.
+    pub direct_hit_rate: f64,
+    /// Determination damage multiplier.
+    pub determination: f64,
+    /// Tenacity multiplier.
+    pub tenacity: f64,
+    /// The global cooldown of weaponskills in seconds.
+    pub skill_speed_gcd: f64,
+    /// The global cooldown of spells in seconds.
+    pub spell_speed_gcd: f64,
+}
+
+impl DerivedStats {
+    /// Computes the derived values from a profile's attributes.
+    ///
+    /// Returns None if any of the needed attributes is missing, which
+    /// happens when a crafter or gatherer is the active class.
+    pub fn from_attributes(attributes: &Attributes) -> Option<Self> {
+        let get = |kind| attributes.get(kind).map(|attrib| attrib.level);
+
+        let critical_hit = get(AttributeKind::CriticalHit)?;
+
+        Some(Self {
+            critical_hit_rate: critical_hit_rate(critical_hit),
+            critical_hit_damage: critical_hit_damage(critical_hit),
+            direct_hit_rate: direct_hit_rate(get(AttributeKind::DirectHit)?),
+            determination: determination_multiplier(get(AttributeKind::Determination)?),
+            tenacity: tenacity_multiplier(get(AttributeKind::Tenacity)?),
+            skill_speed_gcd: gcd(get(AttributeKind::SkillSpeed)?, BASE_GCD),
+            spell_speed_gcd: gcd(get(AttributeKind::SpellSpeed)?, BASE_GCD),
+        })
+    }
+}
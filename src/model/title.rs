@@ -0,0 +1,28 @@
+/// Where a title is shown relative to the character's name.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum TitlePosition {
+    /// Shown before the name, e.g. "Warrior of Light Strawberry Custard".
+    Prefix,
+    /// Shown after the name, e.g. "Strawberry Custard the Unbroken".
+    Suffix,
+}
+
+/// A title a character has chosen to display on their nameplate.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Title {
+    /// The title with surrounding whitespace removed.
+    pub name: String,
+    /// Whether the title goes before or after the character's name.
+    pub position: TitlePosition,
+}
+
+impl Title {
+    /// Combines the title with a character name in the order
+    /// the game displays them.
+    pub fn with_name(&self, character: &str) -> String {
+        match self.position {
+            TitlePosition::Prefix => format!("{} {}", self.name, character),
+            TitlePosition::Suffix => format!("{} {}", character, self.name),
+        }
+    }
+}
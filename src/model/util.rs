@@ -1,18 +1,240 @@
+use encoding_rs::{Encoding, UTF_8};
 use failure::Error;
+use reqwest::{Method, StatusCode};
+use reqwest::header::{CONTENT_TYPE, USER_AGENT};
 use select::document::Document;
+use select::node::Node;
+use select::predicate::Class;
 
 use crate::CLIENT;
+use crate::model::profile::{SearchError, Section};
+
+/// Takes a Document and a search expression, and will return
+/// a `SearchError` if it is not found. Otherwise it will return
+/// the found node.
+macro_rules! ensure_node {
+    ($doc:ident, $search:expr) => {{
+        ensure_node!($doc, $search, 0)
+    }};
+    
+    ($doc:ident, $search:expr, $nth:expr) => {{
+        let node = $doc.find($search).nth($nth);
+        failure::ensure!(node.is_some(), $crate::model::profile::SearchError::NodeNotFound(stringify!($search).to_string() + "(" + stringify!($nth) + ")"));
+        node.unwrap()
+    }};
+}
+
+pub(crate) use ensure_node;
+
+/// Collects the text of a node, turning `<br>` tags and the ends of
+/// paragraphs into line breaks.
+pub(crate) fn text_with_breaks(node: &Node) -> String {
+    let mut text = String::new();
+    for child in node.children() {
+        match child.name() {
+            Some("br") => text.push('\n'),
+            Some("p") | Some("div") => {
+                text.push_str(&text_with_breaks(&child));
+                text.push('\n');
+            }
+            Some(_) => text.push_str(&text_with_breaks(&child)),
+            None => text.push_str(&child.text()),
+        }
+    }
+    text
+}
+
+/// Dates on the lodestone are filled in by a script such as
+/// `ldst_strftime(1583034425, 'YMD')`, so the timestamp is read from it.
+pub(crate) fn parse_strftime_timestamp(script: &str) -> Option<u64> {
+    let start = script.find("ldst_strftime(")? + "ldst_strftime(".len();
+    script[start..]
+        .split(|ch: char| !ch.is_ascii_digit())
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// Returns a `SearchError::SectionPrivate` if a page only holds the
+/// notice shown in place of a section the character has hidden.
+pub(crate) fn ensure_public(doc: &Document, section: Section) -> Result<(), Error> {
+    let private = doc.find(Class("parts__zero"))
+        .any(|notice| {
+            let text = notice.text().to_lowercase();
+            text.contains("private") || text.contains("非公開")
+        });
+    match private {
+        true => Err(SearchError::SectionPrivate(section).into()),
+        false => Ok(()),
+    }
+}
+
+/// The URL base for all lodestone pages.
+pub(crate) static BASE_LODESTONE_URL: &str = "https://na.finalfantasyxiv.com/lodestone/";
 
 /// The URL base for profiles.
-static BASE_PROFILE_URL: &str = "https://na.finalfantasyxiv.com/lodestone/character/";
+pub(crate) static BASE_PROFILE_URL: &str = "https://na.finalfantasyxiv.com/lodestone/character/";
+
+/// The user agent of a phone browser. Some pages, such as mounts and
+/// minions, only include item names in the layout served to phones.
+pub(crate) static MOBILE_USER_AGENT: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 14_0 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/14.0 Mobile/15E148 Safari/604.1";
+
+/// How far into a page to look for a `<meta>` charset declaration.
+const CHARSET_SNIFF_LEN: usize = 1024;
 
-pub(crate) fn load_url(user_id: u32, subpage: Option<&str>) -> Result<Document, Error> {
+/// Loads the raw HTML of a profile page, without parsing it.
+pub(crate) fn load_html(user_id: u32, subpage: Option<&str>) -> Result<String, Error> {
     let subpage = match subpage {
         None => "".to_string(),
         Some(v) => format!("{}/", v)
     };
-    let mut response = CLIENT.get(&format!("{}{}/{}", BASE_PROFILE_URL, user_id, subpage)).send()?;
-    let text = response.text()?;
+    fetch_html(&format!("{}{}/{}", BASE_PROFILE_URL, user_id, subpage))
+}
+
+/// Fetches a page and parses it into a Document.
+pub(crate) fn fetch_document(url: &str) -> Result<Document, Error> {
+    let text = fetch_html(url)?;
     Ok(Document::from(text.as_str()))
 }
 
+/// Fetches a page and decodes its body into text.
+///
+/// Compressed responses are decompressed by the client. The charset is
+/// taken from the Content-Type header, then from a `<meta>` tag near the
+/// start of the page, and otherwise assumed to be UTF-8. Pages are
+/// occasionally served through proxies that drop or change the header,
+/// which is why the page itself is checked as well.
+///
+/// Unsuccessful responses are turned into errors by `status_error`.
+pub(crate) fn fetch_html(url: &str) -> Result<String, Error> {
+    fetch_html_as(url, None)
+}
+
+/// Fetches a file, such as an image, and returns its raw bytes.
+pub(crate) fn fetch_bytes(url: &str) -> Result<Vec<u8>, Error> {
+    let response = send(&Method::GET, url, None)?;
+    if !response.status.is_success() {
+        return Err(status_error(response.status, &String::from_utf8_lossy(&response.body)));
+    }
+
+    Ok(response.body)
+}
+
+/// Requests only the headers of a page and returns its status code.
+pub(crate) fn fetch_status(url: &str) -> Result<StatusCode, Error> {
+    Ok(send(&Method::HEAD, url, None)?.status)
+}
+
+/// Fetches a page as a phone would, and decodes its body into text.
+pub(crate) fn fetch_mobile_html(url: &str) -> Result<String, Error> {
+    fetch_html_as(url, Some(MOBILE_USER_AGENT))
+}
+
+/// Fetches a page with an optional user agent override.
+/// See `fetch_html` for how the body is decoded.
+fn fetch_html_as(url: &str, user_agent: Option<&str>) -> Result<String, Error> {
+    let response = send(&Method::GET, url, user_agent)?;
+
+    let encoding = response.content_type
+        .as_deref()
+        .and_then(charset_from_content_type)
+        .or_else(|| sniff_meta_charset(&response.body))
+        .and_then(|label| Encoding::for_label(label.as_bytes()))
+        .unwrap_or(UTF_8);
+
+    let (text, _, _) = encoding.decode(&response.body);
+
+    if !response.status.is_success() {
+        return Err(status_error(response.status, &text));
+    }
+
+    Ok(text.into_owned())
+}
+
+/// A response with its body read into memory.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct RawResponse {
+    pub status: StatusCode,
+    pub content_type: Option<String>,
+    pub body: Vec<u8>,
+}
+
+/// Sends a request and reads the whole response, whatever its status.
+///
+/// Every request this crate makes goes through here, so with the `vcr`
+/// feature this is where responses are recorded to or replayed from disk.
+fn send(method: &Method, url: &str, user_agent: Option<&str>) -> Result<RawResponse, Error> {
+    #[cfg(feature = "vcr")]
+    {
+        if let Some(response) = crate::vcr::replay(method, url)? {
+            return Ok(response);
+        }
+    }
+
+    let mut request = CLIENT.request(method.clone(), url);
+    if let Some(agent) = user_agent {
+        request = request.header(USER_AGENT, agent);
+    }
+    let response = request.send()?;
+
+    let status = response.status();
+    let content_type = response.headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_owned);
+    let body = response.bytes()?.to_vec();
+
+    let response = RawResponse { status, content_type, body };
+
+    #[cfg(feature = "vcr")]
+    crate::vcr::record(method, url, &response)?;
+
+    Ok(response)
+}
+
+/// Converts an unsuccessful response into an error that tells callers
+/// whether, and how soon, it is worth trying again.
+///
+/// The maintenance page is served with a 503, so a 503 mentioning
+/// maintenance is reported as `Maintenance` rather than a server error.
+pub(crate) fn status_error(status: StatusCode, body: &str) -> Error {
+    match status {
+        StatusCode::NOT_FOUND => SearchError::NotFound.into(),
+        StatusCode::TOO_MANY_REQUESTS => SearchError::RateLimited.into(),
+        StatusCode::SERVICE_UNAVAILABLE if body.to_lowercase().contains("maintenance") => SearchError::Maintenance.into(),
+        status if status.is_server_error() => SearchError::ServerError(status.as_u16()).into(),
+        status => SearchError::UnexpectedStatus(status.as_u16()).into(),
+    }
+}
+
+/// Pulls the charset parameter out of a Content-Type value
+/// such as `text/html; charset=UTF-8`.
+fn charset_from_content_type(content_type: &str) -> Option<&str> {
+    content_type
+        .split(';')
+        .filter_map(|param| {
+            let mut parts = param.splitn(2, '=');
+            let key = parts.next()?.trim();
+            let value = parts.next()?.trim().trim_matches('"');
+            if key.eq_ignore_ascii_case("charset") { Some(value) } else { None }
+        })
+        .next()
+}
+
+/// Looks for a charset declared in a `<meta>` tag at the start of a page,
+/// in either the `<meta charset="...">` or the `http-equiv` form.
+fn sniff_meta_charset(bytes: &[u8]) -> Option<&str> {
+    let head = &bytes[..bytes.len().min(CHARSET_SNIFF_LEN)];
+    //  Only ASCII is needed to find the declaration, so cut at the first invalid byte.
+    let head = match std::str::from_utf8(head) {
+        Ok(text) => text,
+        Err(e) => std::str::from_utf8(&head[..e.valid_up_to()]).ok()?,
+    };
+
+    let lower = head.to_ascii_lowercase();
+    let start = lower.find("charset=")? + "charset=".len();
+    let value = head[start..].trim_start_matches(&['"', '\''][..]);
+    let end = value.find(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '-' || ch == '_'))?;
+
+    Some(&value[..end]).filter(|label| !label.is_empty())
+}
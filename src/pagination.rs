@@ -0,0 +1,164 @@
+use failure::Error;
+use select::document::Document;
+use select::predicate::Class;
+
+use std::thread;
+use std::time::Duration;
+
+/// A single page of results from a paginated lodestone listing.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Page<T> {
+    /// The entries found on this page.
+    pub items: Vec<T>,
+    /// The 1-based number of this page.
+    pub current_page: u32,
+    /// How many pages the listing has in total.
+    pub total_pages: u32,
+}
+
+/// Anything on the lodestone that is split over several numbered pages,
+/// such as search results.
+pub trait Paginated {
+    /// The type of entry listed on each page.
+    type Item;
+
+    /// Fetches a single page of the listing. Pages are 1-based.
+    fn fetch_page(&self, page: u32) -> Result<Page<Self::Item>, Error>;
+}
+
+/// Walks over the pages of a `Paginated` source, only fetching
+/// a page when it is asked for.
+///
+/// A `Pager` is also an `Iterator` over whole pages, so the usual
+/// adapters can be used to stop early, e.g. `pager.take(2)`.
+#[derive(Clone, Debug)]
+pub struct Pager<P> {
+    source: P,
+    next: u32,
+    total: Option<u32>,
+    delay: Option<Duration>,
+    failed: bool,
+}
+
+impl<P: Paginated> Pager<P> {
+    pub fn new(source: P) -> Self {
+        Pager {
+            source,
+            next: 1,
+            total: None,
+            delay: None,
+            failed: false,
+        }
+    }
+
+    /// Waits for the given duration before fetching each page after the
+    /// first, to stay polite when walking long listings.
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+
+    /// Fetches the next page of the listing, or returns None
+    /// once every page has been fetched.
+    ///
+    /// A page that fails to fetch is returned as an error, after which
+    /// no more pages are fetched.
+    pub fn next_page(&mut self) -> Option<Result<Vec<P::Item>, Error>> {
+        if self.failed {
+            return None;
+        }
+
+        if let Some(total) = self.total {
+            if self.next > total {
+                return None;
+            }
+        }
+
+        if let (Some(delay), true) = (self.delay, self.total.is_some()) {
+            thread::sleep(delay);
+        }
+
+        let page = match self.source.fetch_page(self.next) {
+            Ok(page) => page,
+            Err(e) => {
+                //  Stop after an error rather than retrying the same page forever.
+                self.failed = true;
+                return Some(Err(e));
+            }
+        };
+
+        self.next = page.current_page + 1;
+        self.total = Some(page.total_pages);
+
+        Some(Ok(page.items))
+    }
+
+    /// The total number of pages in the listing. This is only known
+    /// after a page has been fetched successfully.
+    pub fn total_pages(&self) -> Option<u32> {
+        self.total
+    }
+
+    /// Turns this into an iterator over single entries rather than pages.
+    /// Pages are still fetched one at a time, as entries are asked for.
+    pub fn items(self) -> Items<P> {
+        Items {
+            pager: self,
+            buffered: Vec::new().into_iter(),
+        }
+    }
+}
+
+/// Iterates over the entries of a `Paginated` source one at a time,
+/// fetching the next page when the current one runs out.
+///
+/// A failed page is yielded as a single error, after which iteration stops.
+pub struct Items<P: Paginated> {
+    pager: Pager<P>,
+    buffered: std::vec::IntoIter<P::Item>,
+}
+
+impl<P: Paginated> Iterator for Items<P> {
+    type Item = Result<P::Item, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.buffered.next() {
+                return Some(Ok(item));
+            }
+
+            match self.pager.next_page()? {
+                Ok(items) => self.buffered = items.into_iter(),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+impl<P: Paginated> Iterator for Pager<P> {
+    type Item = Result<Vec<P::Item>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_page()
+    }
+}
+
+/// Reads the current and total page numbers from the pager on a listing,
+/// which shows text such as "Page 1 of 20". Listings that fit on a single
+/// page have no pager, so they are treated as page 1 of 1.
+pub(crate) fn parse_page_numbers(doc: &Document) -> (u32, u32) {
+    let text = match doc.find(Class("btn__pager__current")).next() {
+        Some(node) => node.text(),
+        None => return (1, 1),
+    };
+
+    let numbers = text
+        .split(|ch: char| !ch.is_ascii_digit())
+        .filter_map(|part| part.parse::<u32>().ok())
+        .collect::<Vec<u32>>();
+
+    match numbers.as_slice() {
+        [current, total] => (*current, *total),
+        _ => (1, 1),
+    }
+}
@@ -1,18 +1,26 @@
 use failure::Error;
+use reqwest::Url;
 use select::document::Document;
 use select::predicate::Class;
 
-use crate::CLIENT;
 use crate::model::profile::Profile;
 use crate::model::datacenter::Datacenter;
+use crate::model::free_company::parse_world_text;
 use crate::model::gc::GrandCompany;
+use crate::model::id::LodestoneId;
 use crate::model::language::Language;
 use crate::model::server::Server;
+use crate::model::util::{ensure_node, fetch_document};
+use crate::pagination::{Page, Paginated, Pager, parse_page_numbers};
 
-use std::fmt::Write;
 use std::collections::HashSet;
+use std::convert::TryFrom;
 
-static BASE_SEARCH_URL: &str = "https://na.finalfantasyxiv.com/lodestone/character/?";
+static BASE_SEARCH_URL: &str = "https://na.finalfantasyxiv.com/lodestone/character/";
+
+static BASE_LINKSHELL_SEARCH_URL: &str = "https://na.finalfantasyxiv.com/lodestone/linkshell/";
+
+static BASE_CWLS_SEARCH_URL: &str = "https://na.finalfantasyxiv.com/lodestone/crossworld_linkshell/";
 
 #[derive(Clone, Debug, Default)]
 pub struct SearchBuilder {
@@ -32,46 +40,149 @@ impl SearchBuilder {
 
     /// Builds the search and executes it, returning a list of profiles
     /// that match the given criteria.
-    pub fn send(self) -> Result<Vec<Profile>, Error> {
-        let mut url = BASE_SEARCH_URL.to_owned();
+    ///
+    /// Only the first page of results is returned. Use `pages` to walk
+    /// over every page of a search.
+    ///
+    /// The builder is not consumed, so it can be kept around as a template
+    /// and cloned with different filters to run similar searches.
+    pub fn send(&self) -> Result<Vec<Profile>, Error> {
+        Ok(self.fetch_page(1)?.items)
+    }
 
-        if let Some(name) = self.character {
-            let _ = write!(url, "q={}&", name);
+    /// Creates a `Pager` over this search, which fetches one page
+    /// of results at a time as they are requested.
+    pub fn pages(&self) -> Pager<Self> {
+        Pager::new(self.clone())
+    }
+
+    /// Builds the url for a page of this search. Every parameter is
+    /// percent-encoded, so names with spaces, apostrophes, or non-ASCII
+    /// characters are sent intact.
+    pub(crate) fn build_url(&self, page: u32) -> String {
+        let mut params: Vec<(&str, String)> = Vec::new();
+
+        if let Some(name) = &self.character {
+            params.push(("q", name.clone()));
         }
 
         if let Some(dc) = self.datacenter {
-            let _ = write!(url, "worldname=_dc_{}&", dc);
+            params.push(("worldname", format!("_dc_{}", dc)));
         }
 
         if let Some(s) = self.server {
-            let _ = write!(url, "worldname={}&", s);
+            params.push(("worldname", s.to_string()));
         }
 
-        self.lang.iter().for_each(|lang| {
-            let _ = match lang {
-                Language::Japanese => write!(url, "blog_lang=ja&"),
-                Language::English => write!(url, "blog_lang=en&"),
-                Language::German => write!(url, "blog_lang=de&"),
-                Language::French => write!(url, "blog_lang=fr&"),
+        let mut langs = self.lang.iter().collect::<Vec<_>>();
+        langs.sort();
+        langs.into_iter().for_each(|lang| {
+            let lang = match lang {
+                Language::Japanese => "ja",
+                Language::English => "en",
+                Language::German => "de",
+                Language::French => "fr",
             };
+            params.push(("blog_lang", lang.into()));
         });
 
-        self.gc.iter().for_each(|gc| {        
-            let _ = match gc {
-                GrandCompany::Unaffiliated => write!(url, "gcid=0&"),
-                GrandCompany::Maelstrom => write!(url, "gcid=1&"),
-                GrandCompany::TwinAdder => write!(url, "gcid=2&"),
-                GrandCompany::ImmortalFlames => write!(url, "gcid=3&"),
-            };
+        let mut gcs = self.gc.iter().collect::<Vec<_>>();
+        gcs.sort();
+        gcs.into_iter().for_each(|gc| {
+            params.push(("gcid", gc.to_gcid().to_string()));
         });
 
-        let url = url.trim_end_matches('&');
+        if page > 1 {
+            params.push(("page", page.to_string()));
+        }
+
+        let mut url = Url::parse(BASE_SEARCH_URL).expect("BASE_SEARCH_URL is a valid url");
+        if !params.is_empty() {
+            url.query_pairs_mut().extend_pairs(params);
+        }
+
+        url.into()
+    }
+
+    /// A character name to search for. This can only be called once,
+    /// and any further calls will simply overwrite the previous name.
+    pub fn character(mut self, name: &str) -> Self {
+        self.set_character(name);
+        self
+    }
+
+    /// Sets the character name to search for in place. See `character`.
+    pub fn set_character(&mut self, name: &str) -> &mut Self {
+        self.character = Some(name.into());
+        self
+    }
+
+    /// A datacenter to search in. Mutually exclusive to server.
+    /// If a server was specified before calling this method,
+    /// it will be replaced by the newer datacenter.
+    pub fn datacenter<D: Into<Datacenter>>(mut self, datacenter: D) -> Self {
+        self.set_datacenter(datacenter);
+        self
+    }
+
+    /// Sets the datacenter to search in, in place. See `datacenter`.
+    pub fn set_datacenter<D: Into<Datacenter>>(&mut self, datacenter: D) -> &mut Self {
+        self.datacenter = Some(datacenter.into());
+        self.server = None;
+        self
+    }
+
+    /// A server to search in. Mutually exclusive to datacenter.
+    /// If a datacenter was specified before calling this method,
+    /// it will be replaced by the newer server.
+    pub fn server<S: Into<Server>>(mut self, server: S) -> Self {
+        self.set_server(server);
+        self
+    }
+
+    /// Sets the server to search in, in place. See `server`.
+    pub fn set_server<S: Into<Server>>(&mut self, server: S) -> &mut Self {
+        self.server = Some(server.into());
+        self.datacenter = None;
+        self
+    }
+
+    /// Which language to filter by.
+    /// You can add multiple languages by calling this multiple times.
+    pub fn lang<L: Into<Language>>(mut self, lang: L) -> Self {
+        self.set_lang(lang);
+        self
+    }
+
+    /// Adds a language filter in place. See `lang`.
+    pub fn set_lang<L: Into<Language>>(&mut self, lang: L) -> &mut Self {
+        self.lang.insert(lang.into());
+        self
+    }
+
+    /// Which grand company to filter by.
+    /// You can add multiple grand company filters by calling this multiple times.
+    pub fn grand_company<G: Into<GrandCompany>>(mut self, gc: G) -> Self {
+        self.set_grand_company(gc);
+        self
+    }
+
+    /// Adds a grand company filter in place. See `grand_company`.
+    pub fn set_grand_company<G: Into<GrandCompany>>(&mut self, gc: G) -> &mut Self {
+        self.gc.insert(gc.into());
+        self
+    }
+}
+
+impl Paginated for SearchBuilder {
+    type Item = Profile;
 
-        let response = CLIENT.get(url).send()?;
-        let text = response.text()?;
-        let doc = Document::from(text.as_str());
+    fn fetch_page(&self, page: u32) -> Result<Page<Profile>, Error> {
+        let doc = fetch_document(&self.build_url(page))?;
 
-        Ok(doc.find(Class("entry__link"))
+        let (current_page, total_pages) = parse_page_numbers(&doc);
+
+        let items = doc.find(Class("entry__link"))
             .filter_map(|node| node
                 .attr("href")
                 .and_then(|text| {
@@ -87,13 +198,130 @@ impl SearchBuilder {
 
                     profile.ok()
                 }))
-            .collect())
+            .collect();
+
+        Ok(Page {
+            items,
+            current_page,
+            total_pages,
+        })
     }
+}
 
-    /// A character name to search for. This can only be called once,
-    /// and any further calls will simply overwrite the previous name.
-    pub fn character(mut self, name: &str) -> Self {
-        self.character = Some(name.into());
+/// The member count brackets the lodestone can filter group searches by.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum MemberCount {
+    /// 1 to 10 members.
+    UpTo10,
+    /// 11 to 30 members.
+    UpTo30,
+    /// 31 to 50 members.
+    UpTo50,
+    /// 51 members or more.
+    Over50,
+}
+
+impl MemberCount {
+    /// The value the lodestone uses for this bracket in query strings,
+    /// e.g. `character_count=11-30`.
+    pub fn to_param(self) -> &'static str {
+        match self {
+            MemberCount::UpTo10 => "1-10",
+            MemberCount::UpTo30 => "11-30",
+            MemberCount::UpTo50 => "31-50",
+            MemberCount::Over50 => "51-",
+        }
+    }
+}
+
+/// A linkshell found by a `LinkshellSearchBuilder`.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct LinkshellSearchResult {
+    /// The lodestone's id for the linkshell.
+    pub id: u64,
+    /// The linkshell's name.
+    pub name: String,
+    /// Which server the linkshell was formed on.
+    pub world: Server,
+    /// Which datacenter the linkshell's server belongs to.
+    pub datacenter: Datacenter,
+}
+
+/// Searches the lodestone for linkshells. Results only hold what the
+/// search page lists, so use `Linkshell::get` for the members.
+#[derive(Clone, Debug, Default)]
+pub struct LinkshellSearchBuilder {
+    server: Option<Server>,
+    datacenter: Option<Datacenter>,
+    name: Option<String>,
+    member_count: HashSet<MemberCount>,
+}
+
+impl LinkshellSearchBuilder {
+    pub fn new() -> Self {
+        LinkshellSearchBuilder {
+            .. Default::default()
+        }
+    }
+
+    /// Builds the search and executes it, returning the first page of
+    /// linkshells that match the given criteria. Use `pages` to walk
+    /// over every page of a search.
+    pub fn send(&self) -> Result<Vec<LinkshellSearchResult>, Error> {
+        Ok(self.fetch_page(1)?.items)
+    }
+
+    /// Creates a `Pager` over this search, which fetches one page
+    /// of results at a time as they are requested.
+    pub fn pages(&self) -> Pager<Self> {
+        Pager::new(self.clone())
+    }
+
+    /// Builds the url for a page of this search, with every parameter
+    /// percent-encoded.
+    pub(crate) fn build_url(&self, page: u32) -> String {
+        let mut params: Vec<(&str, String)> = Vec::new();
+
+        if let Some(name) = &self.name {
+            params.push(("q", name.clone()));
+        }
+
+        if let Some(dc) = self.datacenter {
+            params.push(("worldname", format!("_dc_{}", dc)));
+        }
+
+        if let Some(s) = self.server {
+            params.push(("worldname", s.to_string()));
+        }
+
+        let mut counts = self.member_count.iter().collect::<Vec<_>>();
+        counts.sort();
+        counts.into_iter().for_each(|count| {
+            params.push(("character_count", count.to_param().into()));
+        });
+
+        if page > 1 {
+            params.push(("page", page.to_string()));
+        }
+
+        let mut url = Url::parse(BASE_LINKSHELL_SEARCH_URL).expect("BASE_LINKSHELL_SEARCH_URL is a valid url");
+        if !params.is_empty() {
+            url.query_pairs_mut().extend_pairs(params);
+        }
+
+        url.into()
+    }
+
+    /// A linkshell name to search for. Any further calls will simply
+    /// overwrite the previous name.
+    pub fn name(mut self, name: &str) -> Self {
+        self.set_name(name);
+        self
+    }
+
+    /// Sets the linkshell name to search for in place. See `name`.
+    pub fn set_name(&mut self, name: &str) -> &mut Self {
+        self.name = Some(name.into());
         self
     }
 
@@ -101,6 +329,12 @@ impl SearchBuilder {
     /// If a server was specified before calling this method,
     /// it will be replaced by the newer datacenter.
     pub fn datacenter<D: Into<Datacenter>>(mut self, datacenter: D) -> Self {
+        self.set_datacenter(datacenter);
+        self
+    }
+
+    /// Sets the datacenter to search in, in place. See `datacenter`.
+    pub fn set_datacenter<D: Into<Datacenter>>(&mut self, datacenter: D) -> &mut Self {
         self.datacenter = Some(datacenter.into());
         self.server = None;
         self
@@ -110,22 +344,212 @@ impl SearchBuilder {
     /// If a datacenter was specified before calling this method,
     /// it will be replaced by the newer server.
     pub fn server<S: Into<Server>>(mut self, server: S) -> Self {
+        self.set_server(server);
+        self
+    }
+
+    /// Sets the server to search in, in place. See `server`.
+    pub fn set_server<S: Into<Server>>(&mut self, server: S) -> &mut Self {
         self.server = Some(server.into());
         self.datacenter = None;
         self
     }
 
-    /// Which language to filter by.
-    /// You can add multiple languages by calling this multiple times.
-    pub fn lang<L: Into<Language>>(mut self, lang: L) -> Self {
-        self.lang.insert(lang.into());
+    /// Which member count bracket to filter by.
+    /// You can allow several brackets by calling this multiple times.
+    pub fn member_count(mut self, count: MemberCount) -> Self {
+        self.set_member_count(count);
         self
     }
 
-    /// Which grand company to filter by.
-    /// You can add multiple grand company filters by calling this multiple times.
-    pub fn grand_company<G: Into<GrandCompany>>(mut self, gc: G) -> Self {
-        self.gc.insert(gc.into());
+    /// Allows another member count bracket in place. See `member_count`.
+    pub fn set_member_count(&mut self, count: MemberCount) -> &mut Self {
+        self.member_count.insert(count);
         self
     }
-}
\ No newline at end of file
+}
+
+impl Paginated for LinkshellSearchBuilder {
+    type Item = LinkshellSearchResult;
+
+    fn fetch_page(&self, page: u32) -> Result<Page<LinkshellSearchResult>, Error> {
+        let doc = fetch_document(&self.build_url(page))?;
+
+        let (current_page, total_pages) = parse_page_numbers(&doc);
+
+        Ok(Page {
+            items: parse_linkshell_results(&doc)?,
+            current_page,
+            total_pages,
+        })
+    }
+}
+
+/// Parses the entries of a linkshell search page.
+pub(crate) fn parse_linkshell_results(doc: &Document) -> Result<Vec<LinkshellSearchResult>, Error> {
+    let mut results = Vec::new();
+
+    for entry in doc.find(Class("entry__link--line")) {
+        let id = match LodestoneId::try_from(entry.attr("href").unwrap_or_default()) {
+            Ok(LodestoneId::Linkshell(id)) => id,
+            _ => continue,
+        };
+
+        let (world, datacenter) = parse_world_text(&ensure_node!(entry, Class("entry__world")).text())?;
+
+        results.push(LinkshellSearchResult {
+            id,
+            name: ensure_node!(entry, Class("entry__name")).text().trim().to_string(),
+            world,
+            datacenter,
+        });
+    }
+
+    Ok(results)
+}
+
+/// A cross-world linkshell found by a `CrossworldLinkshellSearchBuilder`.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct CrossworldLinkshellSearchResult {
+    /// The lodestone's id for the linkshell. Unlike other ids this is a hex string.
+    pub id: String,
+    /// The linkshell's name.
+    pub name: String,
+    /// The datacenter the linkshell spans.
+    pub datacenter: Datacenter,
+    /// How many members the linkshell has.
+    pub member_count: u32,
+}
+
+/// Searches the lodestone for cross-world linkshells, which belong to a
+/// datacenter rather than a single server.
+#[derive(Clone, Debug, Default)]
+pub struct CrossworldLinkshellSearchBuilder {
+    datacenter: Option<Datacenter>,
+    name: Option<String>,
+}
+
+impl CrossworldLinkshellSearchBuilder {
+    pub fn new() -> Self {
+        CrossworldLinkshellSearchBuilder {
+            .. Default::default()
+        }
+    }
+
+    /// Builds the search and executes it, returning the first page of
+    /// linkshells that match the given criteria. Use `pages` to walk
+    /// over every page of a search.
+    pub fn send(&self) -> Result<Vec<CrossworldLinkshellSearchResult>, Error> {
+        Ok(self.fetch_page(1)?.items)
+    }
+
+    /// Creates a `Pager` over this search, which fetches one page
+    /// of results at a time as they are requested.
+    pub fn pages(&self) -> Pager<Self> {
+        Pager::new(self.clone())
+    }
+
+    /// Builds the url for a page of this search, with every parameter
+    /// percent-encoded.
+    pub(crate) fn build_url(&self, page: u32) -> String {
+        let mut params: Vec<(&str, String)> = Vec::new();
+
+        if let Some(name) = &self.name {
+            params.push(("q", name.clone()));
+        }
+
+        if let Some(dc) = self.datacenter {
+            params.push(("dcname", dc.to_string()));
+        }
+
+        if page > 1 {
+            params.push(("page", page.to_string()));
+        }
+
+        let mut url = Url::parse(BASE_CWLS_SEARCH_URL).expect("BASE_CWLS_SEARCH_URL is a valid url");
+        if !params.is_empty() {
+            url.query_pairs_mut().extend_pairs(params);
+        }
+
+        url.into()
+    }
+
+    /// A linkshell name to search for. Any further calls will simply
+    /// overwrite the previous name.
+    pub fn name(mut self, name: &str) -> Self {
+        self.set_name(name);
+        self
+    }
+
+    /// Sets the linkshell name to search for in place. See `name`.
+    pub fn set_name(&mut self, name: &str) -> &mut Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// A datacenter to search in.
+    pub fn datacenter<D: Into<Datacenter>>(mut self, datacenter: D) -> Self {
+        self.set_datacenter(datacenter);
+        self
+    }
+
+    /// Sets the datacenter to search in, in place. See `datacenter`.
+    pub fn set_datacenter<D: Into<Datacenter>>(&mut self, datacenter: D) -> &mut Self {
+        self.datacenter = Some(datacenter.into());
+        self
+    }
+}
+
+impl Paginated for CrossworldLinkshellSearchBuilder {
+    type Item = CrossworldLinkshellSearchResult;
+
+    fn fetch_page(&self, page: u32) -> Result<Page<CrossworldLinkshellSearchResult>, Error> {
+        let doc = fetch_document(&self.build_url(page))?;
+
+        let (current_page, total_pages) = parse_page_numbers(&doc);
+
+        Ok(Page {
+            items: parse_cwls_results(&doc)?,
+            current_page,
+            total_pages,
+        })
+    }
+}
+
+/// Parses the entries of a cross-world linkshell search page.
+pub(crate) fn parse_cwls_results(doc: &Document) -> Result<Vec<CrossworldLinkshellSearchResult>, Error> {
+    let mut results = Vec::new();
+
+    for entry in doc.find(Class("entry__link--line")) {
+        //  Links read e.g. "/lodestone/crossworld_linkshell/f5f2e9f5c3a1b8d2/".
+        let href = entry.attr("href").unwrap_or_default();
+        let id = match href.find("crossworld_linkshell/") {
+            Some(start) => href[start + "crossworld_linkshell/".len()..]
+                .split(&['/', '?', '#'][..])
+                .next()
+                .unwrap_or_default(),
+            None => continue,
+        };
+        if id.is_empty() || !id.chars().all(|ch| ch.is_ascii_hexdigit()) {
+            continue;
+        }
+
+        //  Reads e.g. "12 Members", or just the number.
+        let member_count = ensure_node!(entry, Class("entry__linkshell__num"))
+            .text()
+            .replace(",", "")
+            .split(|ch: char| !ch.is_ascii_digit())
+            .find(|digits| !digits.is_empty())
+            .unwrap_or_default()
+            .parse::<u32>()?;
+
+        results.push(CrossworldLinkshellSearchResult {
+            id: id.to_string(),
+            name: ensure_node!(entry, Class("entry__name")).text().trim().to_string(),
+            datacenter: ensure_node!(entry, Class("entry__world")).text().parse::<Datacenter>()?,
+            member_count,
+        });
+    }
+
+    Ok(results)
+}
@@ -0,0 +1,124 @@
+use failure::{Error, Fail};
+use reqwest::{Method, StatusCode};
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use crate::model::util::RawResponse;
+
+/// Errors that can occur while replaying recorded responses.
+#[derive(Fail, Debug)]
+pub enum VcrError {
+    /// Replay mode is on, but no response was recorded for this request.
+    #[fail(display = "No recorded response for '{}'", _0)]
+    MissingRecording(String),
+    /// A recording exists for this request, but could not be read.
+    #[fail(display = "Invalid recording for '{}'", _0)]
+    InvalidRecording(String),
+}
+
+/// Controls whether lodestone responses are recorded to or replayed from disk.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Mode {
+    /// Requests go to the lodestone as normal. This is the default.
+    Off,
+    /// Requests go to the lodestone, and every response is saved
+    /// into the given directory.
+    Record(PathBuf),
+    /// No requests are made; every response, including unsuccessful ones
+    /// and downloaded images, is read from the given directory instead,
+    /// and a missing recording is an error.
+    Replay(PathBuf),
+}
+
+lazy_static::lazy_static! {
+    static ref MODE: RwLock<Mode> = RwLock::new(Mode::Off);
+}
+
+/// Sets the recording mode for all requests made by this crate.
+///
+/// For example, run once against the live site with
+/// `set_mode(Mode::Record("fixtures".into()))`, then use
+/// `set_mode(Mode::Replay("fixtures".into()))` in tests to run
+/// deterministically and offline.
+pub fn set_mode(mode: Mode) {
+    *MODE.write().unwrap_or_else(|e| e.into_inner()) = mode;
+}
+
+/// Gets the current recording mode.
+pub fn mode() -> Mode {
+    MODE.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Returns the recorded response for a request when replaying,
+/// or None if requests should go to the network.
+pub(crate) fn replay(method: &Method, url: &str) -> Result<Option<RawResponse>, Error> {
+    match mode() {
+        Mode::Replay(dir) => {
+            let request = format!("{} {}", method, url);
+            let bytes = fs::read(recording_path(&dir, method, url))
+                .map_err(|_| VcrError::MissingRecording(request.clone()))?;
+            let response = parse_recording(&bytes).ok_or(VcrError::InvalidRecording(request))?;
+            Ok(Some(response))
+        },
+        _ => Ok(None),
+    }
+}
+
+/// Saves a response for a request when recording. Unsuccessful responses
+/// are saved as well, so that errors such as a missing character replay
+/// the same way they happened.
+pub(crate) fn record(method: &Method, url: &str, response: &RawResponse) -> Result<(), Error> {
+    if let Mode::Record(dir) = mode() {
+        fs::create_dir_all(&dir)?;
+
+        //  The first line holds the status and content type, and the body follows as is.
+        let mut file = format!(
+            "{} {}\n",
+            response.status.as_u16(),
+            response.content_type.as_deref().unwrap_or_default(),
+        ).into_bytes();
+        file.extend_from_slice(&response.body);
+
+        fs::write(recording_path(&dir, method, url), file)?;
+    }
+
+    Ok(())
+}
+
+/// Reads a response back out of a file written by `record`.
+fn parse_recording(bytes: &[u8]) -> Option<RawResponse> {
+    let split = bytes.iter().position(|byte| *byte == b'\n')?;
+    let header = std::str::from_utf8(&bytes[..split]).ok()?;
+    let mut parts = header.splitn(2, ' ');
+
+    let status = StatusCode::from_u16(parts.next()?.parse().ok()?).ok()?;
+    let content_type = parts.next()
+        .filter(|content_type| !content_type.is_empty())
+        .map(str::to_owned);
+
+    Some(RawResponse {
+        status,
+        content_type,
+        body: bytes[split + 1..].to_vec(),
+    })
+}
+
+/// Turns a request into a file name that is safe on every platform, e.g.
+/// `GET_https_3A_2F_2Fna.finalfantasyxiv.com_2Flodestone_2Fcharacter_2F11908971_2F`
+///
+/// Anything other than an ASCII letter, digit, `.` or `-` is written as
+/// `_` and its hex value, so every request gets a file of its own.
+fn recording_path(dir: &Path, method: &Method, url: &str) -> PathBuf {
+    let mut name = format!("{}_", method);
+    for byte in url.bytes() {
+        if byte.is_ascii_alphanumeric() || byte == b'.' || byte == b'-' {
+            name.push(byte as char);
+        } else {
+            name.push_str(&format!("_{:02X}", byte));
+        }
+    }
+
+    dir.join(name)
+}